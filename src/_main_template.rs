@@ -54,7 +54,7 @@ fn main() {
     ).unwrap();
 
     // Fonts
-    let mut font_context = Graphics::create_fonts_context();
+    let mut font_context = Graphics::create_fonts_context().unwrap();
     let mut fonts_manager: FontsManager = FontsManager::new(graphics.get_fonts_creator());
     let font_detail = fonts_manager.load_font(&mut font_context, GAME_FONT_DEFAULT_.to_string(), GAME_FONT_DEFAULT_SIZE).unwrap();
     graphics.set_font(font_detail);
@@ -10,6 +10,12 @@ pub struct Animation {
     last_change: Option<SystemTime>,
 }
 
+impl Default for Animation {
+    fn default() -> Self {
+        Animation::new()
+    }
+}
+
 #[allow(dead_code)]
 impl Animation {
     pub fn new() -> Animation {
@@ -34,7 +40,7 @@ impl Animation {
         self.delay_between_two_images_in_ms = Option::None;
     }
 
-    pub fn run(&mut self) -> Option<&Box<dyn ImageFromString>> {
+    pub fn run(&mut self) -> Option<&dyn ImageFromString> {
 
         let mut must_change = true;
 
@@ -68,7 +74,7 @@ impl Animation {
         if self.actual_sprite > self.images.len() {
             return Option::None;
         }
-        return Some(&self.images[self.actual_sprite - 1]);
+        Some(self.images[self.actual_sprite - 1].as_ref())
     }
 
     pub fn restart(&mut self) {
@@ -1,12 +1,19 @@
 
 use std::collections::HashMap;
 use crate::{animations::animation::Animation, graphics::images::ImageFromString};
+use crate::error::{Game2dError, Game2dResult};
 
 pub struct AnimationsManager {
     current: Option<String>,
     animations: HashMap<String, Animation>,
 }
 
+impl Default for AnimationsManager {
+    fn default() -> Self {
+        AnimationsManager::new()
+    }
+}
+
 impl AnimationsManager {
     pub fn new() -> AnimationsManager {
         AnimationsManager {
@@ -15,20 +22,20 @@ impl AnimationsManager {
         }
     }
 
-    pub fn add(&mut self, name: String, animation : Animation) -> Result<bool, String> {
+    pub fn add(&mut self, name: String, animation : Animation) -> Game2dResult<bool> {
 
         if self.animations.contains_key(&name) {
-            return Err(format!("Animation {} already exist", name));
+            return Err(Game2dError::Animation(format!("Animation {} already exist", name)));
         }
         self.animations.insert(name, animation);
 
         Ok(true)
     }
 
-    pub fn set_current(&mut self, name: String) -> Result<bool, String> {
+    pub fn set_current(&mut self, name: String) -> Game2dResult<bool> {
         if !self.animations.contains_key(&name) {
             self.current = Option::None;
-            return Err(format!("Animation {} not exist", name));
+            return Err(Game2dError::Animation(format!("Animation {} not exist", name)));
         } 
         match &self.current {
             Some(x) => {
@@ -53,7 +60,7 @@ impl AnimationsManager {
         Option::None
     }
 
-    pub fn run_current(&mut self) -> Option<&Box<dyn ImageFromString>> {
+    pub fn run_current(&mut self) -> Option<&dyn ImageFromString> {
         let current = self.get_current();
         match current {
             Some(animation) => {
@@ -66,13 +73,8 @@ impl AnimationsManager {
     }
 
     pub fn restart_current(&mut self) {
-        let current = self.get_current();
-        match current {
-            Some(animation) => {
-                animation.restart()
-            },
-            None => {
-            }
+        if let Some(animation) = self.get_current() {
+            animation.restart()
         }
     }
 
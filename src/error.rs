@@ -0,0 +1,60 @@
+/*================================================================
+ *                           E R R O R
+ *================================================================*/
+use std::fmt;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Game2dError {
+    // SDL, window, renderer, fonts context... can't be created
+    Init(String),
+    // An asset file can't be read
+    AssetIo { path: String, message: String },
+    // An asset file is read but can't be decoded
+    Decode { path: String, message: String },
+    // Font not loaded or can't be rendered
+    Font(String),
+    // Image not loaded or quad outside the image
+    Image(String),
+    // Animation not found or already exist
+    Animation(String),
+    // A draw call failed
+    Render(String),
+    // A frame rendered doesn't match its reference
+    Snapshot(String),
+}
+
+pub type Game2dResult<T> = Result<T, Game2dError>;
+
+impl Game2dError {
+    /*
+     * from_asset()
+     *
+     * @Brief : Error while loading an asset : IO if the file can't be
+     *          found, decoding otherwise
+     */
+    pub(crate) fn from_asset(path: &str, message: String) -> Game2dError {
+        if Path::new(path).is_file() {
+            Game2dError::Decode { path: path.to_string(), message }
+        } else {
+            Game2dError::AssetIo { path: path.to_string(), message }
+        }
+    }
+}
+
+impl fmt::Display for Game2dError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Game2dError::Init(e) => write!(f, "Initialization failed : {}", e),
+            Game2dError::AssetIo { path, message } => write!(f, "Can't read {} : {}", path, message),
+            Game2dError::Decode { path, message } => write!(f, "Can't decode {} : {}", path, message),
+            Game2dError::Font(e) => write!(f, "Font error : {}", e),
+            Game2dError::Image(e) => write!(f, "Image error : {}", e),
+            Game2dError::Animation(e) => write!(f, "Animation error : {}", e),
+            Game2dError::Render(e) => write!(f, "Render error : {}", e),
+            Game2dError::Snapshot(e) => write!(f, "Snapshot error : {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Game2dError {}
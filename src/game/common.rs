@@ -1,7 +1,7 @@
 use std::ops::Add;
 
 
-pub const GAME_FONT_DEFAULT_: &'static str = "fonts/Vera.ttf";
+pub const GAME_FONT_DEFAULT_: &str = "fonts/Vera.ttf";
 pub const GAME_FONT_DEFAULT_SIZE: FontSize = 11;
 
 pub trait WithSize {
@@ -80,6 +80,7 @@ pub type Size = u32;
 pub type Transformation = f32;
 pub type Velocity = f32;
 pub type DeltaTime = f32;
+pub type Alpha = f32;
 pub type ColorT = u8;
 pub type FontSize = u16;
 pub type Force = f32;
@@ -91,7 +92,7 @@ pub struct Velocity2d{pub vx: Velocity, pub vy: Velocity }
 #[derive(Clone, Copy)]
 pub struct Point2d{ pub x: Position, pub y: Position }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Position2d{ pub x: Position, pub y: Position }
 
 #[derive(Clone, Copy)]
@@ -116,6 +117,131 @@ impl Force2d {
 #[derive(Clone, Copy)]
 pub struct Scale2d{ pub sx: Transformation, pub sy: Transformation }
 
+/*
+ * Transform2d : affine transformation of positions
+ *
+ *   x' = a * x + c * y + tx
+ *   y' = b * x + d * y + ty
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform2d {
+    pub a: Transformation,
+    pub b: Transformation,
+    pub c: Transformation,
+    pub d: Transformation,
+    pub tx: Position,
+    pub ty: Position,
+}
+
+impl Default for Transform2d {
+    fn default() -> Self {
+        Transform2d::IDENTITY
+    }
+}
+
+impl Transform2d {
+    pub const IDENTITY: Transform2d = Transform2d { a: 1., b: 0., c: 0., d: 1., tx: 0., ty: 0. };
+
+    pub fn translation(tx: Position, ty: Position) -> Transform2d {
+        Transform2d { tx, ty, ..Transform2d::IDENTITY }
+    }
+
+    /*
+     * rotation()
+     *
+     * @Brief : Rotation around the origin (degrees, clockwise on screen)
+     */
+    pub fn rotation(angle: Angle) -> Transform2d {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        Transform2d { a: cos as Transformation, b: sin as Transformation, c: -sin as Transformation, d: cos as Transformation, tx: 0., ty: 0. }
+    }
+
+    pub fn scaling(sx: Transformation, sy: Transformation) -> Transform2d {
+        Transform2d { a: sx, d: sy, ..Transform2d::IDENTITY }
+    }
+
+    /*
+     * then()
+     *
+     * @Brief : This transformation followed by another one
+     */
+    pub fn then(&self, next: &Transform2d) -> Transform2d {
+        Transform2d {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            tx: next.a * self.tx + next.c * self.ty + next.tx,
+            ty: next.b * self.tx + next.d * self.ty + next.ty,
+        }
+    }
+
+    /*
+     * apply()
+     *
+     * @Brief : Transform a position
+     */
+    pub fn apply(&self, x: Position, y: Position) -> (Position, Position) {
+        (self.a * x + self.c * y + self.tx, self.b * x + self.d * y + self.ty)
+    }
+
+    /*
+     * inverse()
+     *
+     * @Brief : Inverse transformation (None if it flattens the plane)
+     */
+    pub fn inverse(&self) -> Option<Transform2d> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() < Transformation::EPSILON {
+            return Option::None;
+        }
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Some(Transform2d { a, b, c, d, tx: -(a * self.tx + c * self.ty), ty: -(b * self.tx + d * self.ty) })
+    }
+
+    /*
+     * is_mirrored()
+     *
+     * @Brief : The transformation flips the plane (negative determinant)
+     */
+    pub fn is_mirrored(&self) -> bool {
+        self.a * self.d - self.b * self.c < 0.
+    }
+
+    /*
+     * get_scale()
+     *
+     * @Brief : Scale of the x and y axes (sx is negative if mirrored :
+     *          the transformation is a rotation of a scaling)
+     */
+    pub fn get_scale(&self) -> Scale2d {
+        let sx = self.a.hypot(self.b);
+        Scale2d { sx: if self.is_mirrored() { -sx } else { sx }, sy: self.c.hypot(self.d) }
+    }
+
+    /*
+     * get_rotation()
+     *
+     * @Brief : Rotation (degrees) applied after the scale of get_scale()
+     */
+    pub fn get_rotation(&self) -> Angle {
+        let (a, b) = match self.is_mirrored() {
+            true => (-self.a, -self.b),
+            false => (self.a, self.b),
+        };
+        (b as Angle).atan2(a as Angle).to_degrees()
+    }
+
+    /*
+     * is_axis_aligned()
+     *
+     * @Brief : No rotation (rectangles stay rectangles)
+     */
+    pub fn is_axis_aligned(&self) -> bool {
+        self.b.abs() < Transformation::EPSILON && self.c.abs() < Transformation::EPSILON
+    }
+}
+
 
 impl Add<Velocity2d> for Position2d {
 
@@ -128,3 +254,69 @@ impl Add<Velocity2d> for Position2d {
         }
     }
 }  
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: (Position, Position), expected: (Position, Position)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-4 && (actual.1 - expected.1).abs() < 1e-4,
+            "{:?} instead of {:?}", actual, expected
+        );
+    }
+
+    #[test]
+    fn transform_then() {
+        // Scale, then rotate a quarter, then move
+        let transform = Transform2d::scaling(2., 3.)
+            .then(&Transform2d::rotation(90.))
+            .then(&Transform2d::translation(10., 20.));
+        assert_near(transform.apply(1., 0.), (10., 22.));
+        assert_near(transform.apply(0., 1.), (7., 20.));
+        assert_eq!(Transform2d::IDENTITY.then(&transform), transform);
+    }
+
+    #[test]
+    fn transform_inverse() {
+        let transform = Transform2d::rotation(30.)
+            .then(&Transform2d::scaling(-2., 0.5))
+            .then(&Transform2d::translation(-4., 7.));
+        let inverse = transform.inverse().unwrap();
+        let (x, y) = transform.apply(3., -5.);
+        assert_near(inverse.apply(x, y), (3., -5.));
+
+        assert!(Transform2d::scaling(0., 1.).inverse().is_none());
+    }
+
+    #[test]
+    fn transform_scale_and_rotation() {
+        let transform = Transform2d::scaling(2., 3.).then(&Transform2d::rotation(45.));
+        let scale = transform.get_scale();
+        assert_near((scale.sx, scale.sy), (2., 3.));
+        assert!((transform.get_rotation() - 45.).abs() < 1e-3);
+        assert!(!transform.is_mirrored());
+    }
+
+    #[test]
+    fn transform_mirror() {
+        // Flipped horizontally, then rotated
+        let transform = Transform2d::scaling(-2., 1.).then(&Transform2d::rotation(30.));
+        assert!(transform.is_mirrored());
+        let scale = transform.get_scale();
+        assert_near((scale.sx, scale.sy), (-2., 1.));
+        assert!((transform.get_rotation() - 30.).abs() < 1e-3);
+
+        // Flipped vertically : same as flipped horizontally and half a turn
+        let transform = Transform2d::scaling(1., -1.);
+        assert!(transform.is_mirrored());
+        let scale = transform.get_scale();
+        assert_near((scale.sx, scale.sy), (-1., 1.));
+        assert!((transform.get_rotation().abs() - 180.).abs() < 1e-3);
+
+        // Both : a half turn, not a mirror
+        let transform = Transform2d::scaling(-1., -1.);
+        assert!(!transform.is_mirrored());
+        assert!((transform.get_rotation().abs() - 180.).abs() < 1e-3);
+    }
+}
@@ -3,20 +3,31 @@ use std::time::{Instant, Duration};
 
 use crate::graphics::fonts::FontsManager;
 use crate::graphics::graphics::Graphics;
+use crate::graphics::window::WindowEvents;
+use crate::inputs::event::InputEvent;
+use crate::inputs::gamepad::Gamepads;
+use crate::inputs::input_map::InputMap;
 use crate::inputs::keyboard::{Keys, Keyboard};
-use crate::inputs::mouse::Mouse;
+use crate::inputs::mouse::{Mouse, MouseButtons};
+use crate::inputs::recording::{InputPlayback, InputRecording};
+use crate::inputs::text::TextInput;
 use sdl2::event::Event;
 
-use super::common::{Fps, DeltaTime, Position};
+use super::common::{Alpha, Fps, DeltaTime, Position};
 use super::inputs::Inputs;
+use super::scene::{Scene, SceneTransition};
 
-#[allow(dead_code)]
-pub type GameCallbackDraw<T>        = fn(&mut Graphics, &mut Option<T>, &mut Inputs, &mut Option<FontsManager>);
-pub type GameCallbackKeyPressed<T>  = fn(&mut Graphics, &mut Option<T>, &Keys);
-pub type GameCallbackLoad<T>        = fn(&mut Graphics, &mut Option<T>);
-pub type GameCallbackQuit<T>        = fn(&mut Graphics, &mut Option<T>);
-pub type GameCallbackUpdate<T>      = fn(&mut Graphics, &mut Option<T>, &mut Inputs, DeltaTime);
+pub const GAME_DEFAULT_MAX_UPDATE_STEPS: u32 = 5;
 
+#[allow(dead_code)]
+pub type GameCallbackDraw<T>        = Box<dyn FnMut(&mut Graphics, &mut Option<T>, &mut Inputs, &mut Option<FontsManager>)>;
+pub type GameCallbackDrawInterpolated<T> = Box<dyn FnMut(&mut Graphics, &mut Option<T>, &mut Inputs, &mut Option<FontsManager>, Alpha)>;
+pub type GameCallbackKeyPressed<T>  = Box<dyn FnMut(&mut Graphics, &mut Option<T>, &Keys)>;
+pub type GameCallbackMousePressed<T> = Box<dyn FnMut(&mut Graphics, &mut Option<T>, &MouseButtons, Position, Position)>;
+pub type GameCallbackLoad<T>        = Box<dyn FnMut(&mut Graphics, &mut Option<T>)>;
+pub type GameCallbackQuit<T>        = Box<dyn FnMut(&mut Graphics, &mut Option<T>)>;
+pub type GameCallbackUpdate<T>      = Box<dyn FnMut(&mut Graphics, &mut Option<T>, &mut Inputs, DeltaTime)>;
+pub type GameCallbackWindowEvent<T> = Box<dyn FnMut(&mut Graphics, &mut Option<T>, &WindowEvents)>;
 
 pub trait Updatable {
     fn update(&mut self, graphics: &mut Graphics, inputs: &mut Inputs, dt: &DeltaTime);
@@ -29,13 +40,30 @@ pub struct Game<T> {
 
     max_fps: Option<f32>,
 
+    tick_rate: Option<Fps>,
+    max_update_steps: u32,
+    fixed_dt: Option<DeltaTime>,
+
+    pause_on_focus_lost: bool,
+    paused: bool,
+
     params: Option<T>,
 
+    input_map: InputMap,
+    input_recording: Option<InputRecording>,
+    input_playback: Option<InputPlayback>,
+
+    scenes: Vec<Box<dyn Scene<T>>>,
+    scenes_pending: Vec<Box<dyn Scene<T>>>,
+
     callback_keypressed : Option<GameCallbackKeyPressed<T>>,
+    callback_mousepressed : Option<GameCallbackMousePressed<T>>,
     callback_update : Option<GameCallbackUpdate<T>>,
     callback_draw   : Option<GameCallbackDraw<T>>,
+    callback_draw_interpolated : Option<GameCallbackDrawInterpolated<T>>,
     callback_quit   : Option<GameCallbackQuit<T>>,
     callback_load   : Option<GameCallbackLoad<T>>,
+    callback_window_event : Option<GameCallbackWindowEvent<T>>,
 
 }
 
@@ -51,12 +79,25 @@ impl<T> Game<T> {
         Game {
             graphics,
             max_fps: Option::None,
+            tick_rate: Option::None,
+            max_update_steps: GAME_DEFAULT_MAX_UPDATE_STEPS,
+            fixed_dt: Option::None,
+            pause_on_focus_lost: false,
+            paused: false,
             params: Option::None, 
+            input_map: InputMap::default(),
+            input_recording: Option::None,
+            input_playback: Option::None,
+            scenes: Vec::new(),
+            scenes_pending: Vec::new(),
             callback_update: Option::None, 
             callback_draw: Option::None, 
+            callback_draw_interpolated: Option::None,
             callback_quit: Option::None, 
             callback_load: Option::None,
             callback_keypressed: Option::None,
+            callback_mousepressed: Option::None,
+            callback_window_event: Option::None,
         }
     }
 
@@ -70,23 +111,154 @@ impl<T> Game<T> {
         self
     }
 
+    /*
+     * set_input_map()
+     * 
+     * @Brief : Set the actions bindings (inputs.map in callbacks)
+     */
+    pub fn set_input_map(&mut self, input_map: InputMap) -> &mut Self {
+        self.input_map = input_map;
+        self
+    }
+
+    /*
+     * get_input_map()
+     * 
+     * @Brief : Actions bindings (with the changes made in the callbacks
+     *          during the last run)
+     */
+    pub fn get_input_map(&self) -> &InputMap {
+        &self.input_map
+    }
+
+    /*
+     * push_scene()
+     * 
+     * @Brief : Add a scene on the scenes stack (the last pushed is on top)
+     */
+    pub fn push_scene(&mut self, scene: Box<dyn Scene<T>>) -> &mut Self {
+        self.scenes_pending.push(scene);
+        self
+    }
+
     /*
      * set_max_fps()
      * 
-     * @Brief : Set max FPS 
+     * @Brief : Set max FPS (render rate)
      */
     pub fn set_max_fps(&mut self, max_fps: Option<Fps>) -> &mut Self {
         self.max_fps = max_fps;
         self
     }
 
+    /*
+     * set_tick_rate()
+     * 
+     * @Brief : Use a fixed timestep for updates (ticks per second).
+     *          None to go back to a variable dt.
+     */
+    pub fn set_tick_rate(&mut self, tick_rate: Option<Fps>) -> &mut Self {
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    /*
+     * set_max_update_steps()
+     * 
+     * @Brief : Max number of fixed updates to catch up in one frame
+     */
+    pub fn set_max_update_steps(&mut self, max_update_steps: u32) -> &mut Self {
+        self.max_update_steps = max_update_steps.max(1);
+        self
+    }
+
+    /*
+     * set_pause_on_focus_lost()
+     * 
+     * @Brief : Stop updating the game while the window hasn't the focus
+     *          (it is still drawn)
+     */
+    pub fn set_pause_on_focus_lost(&mut self, pause_on_focus_lost: bool) -> &mut Self {
+        self.pause_on_focus_lost = pause_on_focus_lost;
+        self
+    }
+
+    /*
+     * is_paused()
+     * 
+     * @Brief : The game is paused (focus lost)
+     */
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /*
+     * set_fixed_dt()
+     * 
+     * @Brief : Use the same dt for each frame instead of the clock
+     *          (deterministic runs, playback). None to use the clock.
+     */
+    pub fn set_fixed_dt(&mut self, fixed_dt: Option<DeltaTime>) -> &mut Self {
+        self.fixed_dt = fixed_dt;
+        self
+    }
+
+    /*
+     * set_input_recording()
+     * 
+     * @Brief : Record the input events processed by run()
+     *          (see get_input_recording())
+     */
+    pub fn set_input_recording(&mut self, record: bool) -> &mut Self {
+        self.input_recording = if record { Some(InputRecording::new()) } else { Option::None };
+        self
+    }
+
+    /*
+     * get_input_recording()
+     * 
+     * @Brief : Input events recorded during the last run
+     */
+    pub fn get_input_recording(&self) -> Option<&InputRecording> {
+        self.input_recording.as_ref()
+    }
+
+    /*
+     * set_input_playback()
+     * 
+     * @Brief : Play recorded input events instead of the SDL ones
+     *          (only quitting the window is still handled), with the
+     *          fixed dt of the recording
+     */
+    pub fn set_input_playback(&mut self, recording: Option<InputRecording>) -> &mut Self {
+        self.input_playback = recording.map(InputPlayback::new);
+        self
+    }
+
     /*
      * set_callback_draw()
      * 
      * @Brief : Callback to call in each draw
      */
-    pub fn set_callback_draw(&mut self, callback: GameCallbackDraw<T>) -> &mut Self {
-        self.callback_draw = Some(callback);
+    pub fn set_callback_draw<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&mut Graphics, &mut Option<T>, &mut Inputs, &mut Option<FontsManager>) + 'static,
+    {
+        self.callback_draw = Some(Box::new(callback));
+        self
+    }
+
+    /*
+     * set_callback_draw_interpolated()
+     * 
+     * @Brief : Callback to call in each draw, with the interpolation
+     *          alpha between the last two fixed updates
+     */
+    pub fn set_callback_draw_interpolated<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&mut Graphics, &mut Option<T>, &mut Inputs, &mut Option<FontsManager>, Alpha) + 'static,
+    {
+        self.callback_draw_interpolated = Some(Box::new(callback));
         self
     }
 
@@ -95,8 +267,11 @@ impl<T> Game<T> {
      * 
      * @Brief : Callback to call in load
      */
-    pub fn set_callback_load(&mut self, callback: GameCallbackLoad<T>) -> &mut Self {
-        self.callback_load = Some(callback);
+    pub fn set_callback_load<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&mut Graphics, &mut Option<T>) + 'static,
+    {
+        self.callback_load = Some(Box::new(callback));
         self
     }
 
@@ -105,8 +280,11 @@ impl<T> Game<T> {
      * 
      * @Brief : Callback to call in each update
      */
-    pub fn set_callback_update(&mut self, callback: GameCallbackUpdate<T>) -> &mut Self {
-        self.callback_update = Some(callback);
+    pub fn set_callback_update<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&mut Graphics, &mut Option<T>, &mut Inputs, DeltaTime) + 'static,
+    {
+        self.callback_update = Some(Box::new(callback));
         self
     }
 
@@ -115,8 +293,11 @@ impl<T> Game<T> {
      * 
      * @Brief : Callback to call before quit
      */
-    pub fn set_callback_quit(&mut self, callback: GameCallbackQuit<T>) -> &mut Self {
-        self.callback_quit = Some(callback);
+    pub fn set_callback_quit<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&mut Graphics, &mut Option<T>) + 'static,
+    {
+        self.callback_quit = Some(Box::new(callback));
         self
     }
 
@@ -125,8 +306,38 @@ impl<T> Game<T> {
      * 
      * @Brief : Callback to call a each key pressed (UP and DOWN)
      */
-    pub fn set_callback_key_pressed(&mut self, callback: GameCallbackKeyPressed<T>) -> &mut Self {
-        self.callback_keypressed = Some(callback);
+    pub fn set_callback_key_pressed<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&mut Graphics, &mut Option<T>, &Keys) + 'static,
+    {
+        self.callback_keypressed = Some(Box::new(callback));
+        self
+    }
+
+    /*
+     * set_callback_mouse_pressed()
+     * 
+     * @Brief : Callback to call a each mouse button pressed (with the
+     *          position of the mouse)
+     */
+    pub fn set_callback_mouse_pressed<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&mut Graphics, &mut Option<T>, &MouseButtons, Position, Position) + 'static,
+    {
+        self.callback_mousepressed = Some(Box::new(callback));
+        self
+    }
+
+    /*
+     * set_callback_window_event()
+     * 
+     * @Brief : Callback to call a each window event (resize, focus...)
+     */
+    pub fn set_callback_window_event<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: FnMut(&mut Graphics, &mut Option<T>, &WindowEvents) + 'static,
+    {
+        self.callback_window_event = Some(Box::new(callback));
         self
     }
 
@@ -136,88 +347,648 @@ impl<T> Game<T> {
      * @Brief : Main loop of the game
      */
     pub fn run(&mut self, fonts_manager: &mut Option<FontsManager>) -> &mut Self {
+        self.main_loop(fonts_manager, Option::None)
+    }
+
+    /*
+     * run_frames()
+     * 
+     * @Brief : Main loop of the game, stopped after a number of frames
+     *          (tests, headless graphics)
+     */
+    pub fn run_frames(&mut self, frames: u64, fonts_manager: &mut Option<FontsManager>) -> &mut Self {
+        self.main_loop(fonts_manager, Some(frames))
+    }
+
+    /*
+     * main_loop()
+     * 
+     * @Brief : Main loop of the game
+     */
+    fn main_loop(&mut self, fonts_manager: &mut Option<FontsManager>, max_frames: Option<u64>) -> &mut Self {
 
         let mut inputs: Inputs = Inputs {
             keyboard: Keyboard::default(),
             mouse: Mouse::default(),
+            gamepads: match self.input_playback {
+                Some(_) => Gamepads::default(),
+                None => Gamepads::new(self.graphics.sdl_game_controller.clone()),
+            },
+            text: TextInput::default(),
+            // Given back to the game after the loop
+            map: std::mem::take(&mut self.input_map),
+        };
+
+        // Playback : same dt as the run recorded
+        let fixed_dt = match &self.input_playback {
+            Some(playback) => playback.get_fixed_dt().or(self.fixed_dt),
+            None => self.fixed_dt,
         };
+        if let Some(recording) = &mut self.input_recording {
+            recording.set_fixed_dt(fixed_dt);
+        }
 
         // Load
-        if let Some(l) = self.callback_load {
+        if let Some(l) = &mut self.callback_load {
              l(&mut self.graphics, &mut self.params);
         }
+        for scene in std::mem::take(&mut self.scenes_pending) {
+            self.apply_transition(SceneTransition::Push(scene));
+        }
+
+        // SDL starts the text input by default
+        let mut text_input_active = false;
+        self.graphics.set_text_input(false);
 
         let mut timer_start = Instant::now();
+        let mut accumulator: DeltaTime = 0.;
+        let mut frame: u64 = 0;
 
         // Main loop
         'mainloop: loop {
+            if let Some(max_frames) = max_frames {
+                if frame >= max_frames {
+                    break 'mainloop;
+                }
+            }
+            let frame_index = frame;
+            frame += 1;
+
+            let frame_start = Instant::now();
+
             // Before drawing
             self.graphics.begin_draw();
 
-            // Keys 
-            for event in self.graphics.sdl_event_pump.poll_iter() {
-                match event {
-                    Event::KeyDown { timestamp: _, window_id: _, keycode, scancode: _, keymod: _, repeat: _ } => {
-                        inputs.keyboard.add_key_down(Keyboard::_sdl_keycode_to_key(keycode.unwrap()));
-                    },
-                    Event::KeyUp { timestamp: _, window_id: _, keycode, scancode: _, keymod: _, repeat: _ } => {
-                        inputs.keyboard.add_key_up(Keyboard::_sdl_keycode_to_key(keycode.unwrap()));
-                    },
-                    Event::MouseMotion { timestamp: _, window_id: __id, which: _, mousestate: _, x, y, xrel: _, yrel: _ } => {
-                        inputs.mouse.set_x(x as Position);
-                        inputs.mouse.set_y(y as Position);
+            // Text input started / stopped
+            if inputs.text.is_active() != text_input_active {
+                text_input_active = inputs.text.is_active();
+                self.graphics.set_text_input(text_input_active);
+            }
+
+            // Input events (no SDL events without window)
+            let (mut events, window_events) = self.poll_events(&mut inputs);
+            if let Some(playback) = &mut self.input_playback {
+                let quit = events.contains(&InputEvent::Quit);
+                events = playback.get_events(frame_index);
+                if quit {
+                    events.push(InputEvent::Quit);
+                }
+            }
+            if let Some(recording) = &mut self.input_recording {
+                for event in events.iter() {
+                    recording.push(frame_index, event.clone());
+                }
+            }
+
+            // Edges not seen by an update yet (frame without update) :
+            // their callbacks have already been called
+            let keys_latched = inputs.keyboard.get_keys_pressed();
+            let buttons_latched = inputs.mouse.get_buttons_pressed();
+            for event in events.iter() {
+                if *event == InputEvent::Quit {
+                    self.quit();
+                    break 'mainloop;
+                }
+                inputs.apply(event);
+            }
+
+            // Window events
+            for window_event in window_events.iter() {
+                match window_event {
+                    WindowEvents::FocusLost if self.pause_on_focus_lost => self.paused = true,
+                    WindowEvents::FocusGained if self.paused => {
+                        // Don't catch up the time spent in pause
+                        self.paused = false;
+                        timer_start = Instant::now();
+                        accumulator = 0.;
                     },
-                    Event::Quit { .. } => {
-                        if let Some(q) = &mut self.callback_quit {
-                            q(&mut self.graphics, &mut self.params);
-                        }
-                        break 'mainloop;
-                    }
-                    _ => {
-                    }
+                    _ => {},
+                }
+                if !self.window_event(window_event) {
+                    break 'mainloop;
                 }
             }
 
             // Keys released callback ?
-            if let Some(k) = self.callback_keypressed {
+            if self.callback_keypressed.is_some() || !self.scenes.is_empty() {
                 let keys = inputs.keyboard.get_keys_pressed();
-                for key in keys.iter() {
-                    k(&mut self.graphics,  &mut self.params, key);
+                for key in keys.iter().filter(|k| !keys_latched.contains(k)) {
+                    if !self.key_pressed(key) {
+                        break 'mainloop;
+                    }
+                }
+            }
+
+            // Mouse buttons pressed callback ?
+            if self.callback_mousepressed.is_some() || !self.scenes.is_empty() {
+                let buttons = inputs.mouse.get_buttons_pressed();
+                for button in buttons.iter().filter(|b| !buttons_latched.contains(b)) {
+                    if !self.mouse_pressed(button, inputs.mouse.get_x(), inputs.mouse.get_y()) {
+                        break 'mainloop;
+                    }
                 }
             }
 
             // Update callback ?
-            let mut dt =  timer_start.elapsed().as_secs_f32();
+            let mut dt = fixed_dt.unwrap_or_else(|| timer_start.elapsed().as_secs_f32());
             timer_start = Instant::now();
-            if let Some(u) = self.callback_update {
-               
-                // Limit FPS
-                if let Some(max_fps) = self.max_fps {
-                    let limit_fps = 1. / max_fps;
-                    if dt > limit_fps {
-                        dt = limit_fps;
+            let mut alpha: Alpha = 1.;
+            let fixed_step = !self.paused && matches!(self.tick_rate, Some(tick_rate) if tick_rate > 0.);
+
+            match self.tick_rate {
+                // Paused : no update
+                _ if self.paused => {},
+                // Fixed timestep
+                Some(tick_rate) if tick_rate > 0. => {
+                    let step: DeltaTime = 1. / tick_rate;
+                    accumulator += dt;
+
+                    let mut steps = 0;
+                    while accumulator >= step && steps < self.max_update_steps {
+                        if !self.update(&mut inputs, step) {
+                            break 'mainloop;
+                        }
+                        // Edges are seen by one update only (kept until then)
+                        inputs.next_frame(step);
+                        accumulator -= step;
+                        steps += 1;
+                    }
+
+                    // Too late : drop the remaining steps (avoid spiral of death)
+                    if accumulator >= step {
+                        accumulator %= step;
                     }
-                }
 
-                u(&mut self.graphics, &mut self.params, &mut inputs, dt);
+                    alpha = accumulator / step;
+                },
+                // Variable timestep
+                _ => {
+                    // Limit FPS
+                    if let Some(max_fps) = self.max_fps {
+                        let limit_fps = 1. / max_fps;
+                        if dt > limit_fps {
+                            dt = limit_fps;
+                        }
+                    }
+
+                    if !self.update(&mut inputs, dt) {
+                        break 'mainloop;
+                    }
+                }
             }
 
             // Draw callback ?
-            if let Some(d) = self.callback_draw {
-                d(&mut self.graphics, &mut self.params, &mut inputs, fonts_manager);
-            }
+            self.draw(&mut inputs, fonts_manager, alpha);
 
             // After drawing
             self.graphics.end_draw();
 
-            // Limit FPS
+            // Variable timestep : keyboard, mouse, gamepads and text edges
+            // are valid for this frame only
+            if !fixed_step {
+                inputs.next_frame(fixed_dt.unwrap_or_else(|| frame_start.elapsed().as_secs_f32()));
+            }
+
+            // Limit FPS (sleep only the remaining time of the frame)
             if let Some(fps) = self.max_fps {
                 if fps > 0. {
-                    ::std::thread::sleep(Duration::new(0, 1_000_000_000u32 / fps as u32));
+                    let frame_duration = Duration::from_secs_f32(1. / fps);
+                    let frame_elapsed = frame_start.elapsed();
+                    if frame_elapsed < frame_duration {
+                        ::std::thread::sleep(frame_duration - frame_elapsed);
+                    }
                 }
             }
 
         }
+
+        // Rebindings made during the run are kept
+        self.input_map = inputs.map;
         self
     }
-}
\ No newline at end of file
+
+    /*
+     * poll_events()
+     * 
+     * @Brief : Input events (gamepads included) and window events of
+     *          the frame from SDL
+     */
+    fn poll_events(&mut self, inputs: &mut Inputs) -> (Vec<InputEvent>, Vec<WindowEvents>) {
+        let sdl_events: Vec<Event> = match &mut self.graphics.sdl_event_pump {
+            Some(event_pump) => event_pump.poll_iter().collect(),
+            None => Vec::new(),
+        };
+
+        let mut events = Vec::new();
+        let mut window_events = Vec::new();
+        for event in sdl_events.iter() {
+            match event {
+                Event::ControllerDeviceAdded { timestamp: _, which } => {
+                    if let Ok(Some(connected)) = inputs.gamepads.open(*which) {
+                        events.push(connected);
+                    }
+                },
+                Event::Window { .. } => {
+                    if let Some(window_event) = WindowEvents::from_sdl_event(event) {
+                        window_events.push(window_event);
+                    }
+                },
+                _ => {
+                    if let Some(event) = InputEvent::from_sdl_event(event) {
+                        events.push(self.to_logical(event));
+                    }
+                }
+            }
+        }
+        events.extend(inputs.gamepads.poll());
+        (events, window_events)
+    }
+
+    /*
+     * to_logical()
+     * 
+     * @Brief : Map the mouse positions of an event to the logical
+     *          resolution of the graphics
+     */
+    fn to_logical(&self, event: InputEvent) -> InputEvent {
+        let view = match self.graphics.get_logical_view() {
+            Some(view) => view,
+            None => return event,
+        };
+        match event {
+            InputEvent::MouseMotion { x, y, xrel, yrel } => {
+                let (x, y) = view.to_logical(x, y);
+                InputEvent::MouseMotion { x, y, xrel: xrel / view.sx, yrel: yrel / view.sy }
+            },
+            InputEvent::MouseButtonDown { button, x, y } => {
+                let (x, y) = view.to_logical(x, y);
+                InputEvent::MouseButtonDown { button, x, y }
+            },
+            InputEvent::MouseButtonUp { button, x, y } => {
+                let (x, y) = view.to_logical(x, y);
+                InputEvent::MouseButtonUp { button, x, y }
+            },
+            _ => event,
+        }
+    }
+
+    /*
+     * window_event()
+     * 
+     * @Brief : Send a window event to the callback and the top scene.
+     *          Return false if the game must quit
+     */
+    fn window_event(&mut self, event: &WindowEvents) -> bool {
+        if let Some(w) = &mut self.callback_window_event {
+            w(&mut self.graphics, &mut self.params, event);
+        }
+
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.window_event(&mut self.graphics, &mut self.params, event),
+            None => SceneTransition::None,
+        };
+        self.apply_transition(transition)
+    }
+
+    /*
+     * key_pressed()
+     * 
+     * @Brief : Send a key pressed to the callback and the top scene.
+     *          Return false if the game must quit
+     */
+    fn key_pressed(&mut self, key: &Keys) -> bool {
+        if let Some(k) = &mut self.callback_keypressed {
+            k(&mut self.graphics, &mut self.params, key);
+        }
+
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.key_pressed(&mut self.graphics, &mut self.params, key),
+            None => SceneTransition::None,
+        };
+        self.apply_transition(transition)
+    }
+
+    /*
+     * mouse_pressed()
+     * 
+     * @Brief : Send a mouse button pressed to the callback and the top scene.
+     *          Return false if the game must quit
+     */
+    fn mouse_pressed(&mut self, button: &MouseButtons, x: Position, y: Position) -> bool {
+        if let Some(m) = &mut self.callback_mousepressed {
+            m(&mut self.graphics, &mut self.params, button, x, y);
+        }
+
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.mouse_pressed(&mut self.graphics, &mut self.params, button, x, y),
+            None => SceneTransition::None,
+        };
+        self.apply_transition(transition)
+    }
+
+    /*
+     * update()
+     * 
+     * @Brief : One update of the callback and the top scene.
+     *          Return false if the game must quit
+     */
+    fn update(&mut self, inputs: &mut Inputs, dt: DeltaTime) -> bool {
+        if let Some(u) = &mut self.callback_update {
+            u(&mut self.graphics, &mut self.params, inputs, dt);
+        }
+
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.update(&mut self.graphics, &mut self.params, inputs, dt),
+            None => SceneTransition::None,
+        };
+        self.apply_transition(transition)
+    }
+
+    /*
+     * draw()
+     * 
+     * @Brief : Draw the callbacks and the visible scenes (from bottom to top)
+     */
+    fn draw(&mut self, inputs: &mut Inputs, fonts_manager: &mut Option<FontsManager>, alpha: Alpha) {
+        if let Some(d) = &mut self.callback_draw {
+            d(&mut self.graphics, &mut self.params, inputs, fonts_manager);
+        }
+        if let Some(d) = &mut self.callback_draw_interpolated {
+            d(&mut self.graphics, &mut self.params, inputs, fonts_manager, alpha);
+        }
+
+        // First visible scene
+        let mut first = self.scenes.len();
+        while first > 0 {
+            first -= 1;
+            if !self.scenes[first].draw_below() {
+                break;
+            }
+        }
+
+        for scene in self.scenes.iter_mut().skip(first) {
+            scene.draw(&mut self.graphics, &mut self.params, inputs, fonts_manager, alpha);
+        }
+    }
+
+    /*
+     * quit()
+     * 
+     * @Brief : Call quit on scenes (top to bottom) and the callback
+     */
+    fn quit(&mut self) {
+        for scene in self.scenes.iter_mut().rev() {
+            scene.quit(&mut self.graphics, &mut self.params);
+        }
+        if let Some(q) = &mut self.callback_quit {
+            q(&mut self.graphics, &mut self.params);
+        }
+    }
+
+    /*
+     * apply_transition()
+     * 
+     * @Brief : Update the scenes stack.
+     *          Return false if the game must quit
+     */
+    fn apply_transition(&mut self, transition: SceneTransition<T>) -> bool {
+        match transition {
+            SceneTransition::None => {},
+            SceneTransition::Push(mut scene) => {
+                if let Some(top) = self.scenes.last_mut() {
+                    top.exit(&mut self.graphics, &mut self.params);
+                }
+                scene.load(&mut self.graphics, &mut self.params);
+                scene.enter(&mut self.graphics, &mut self.params);
+                self.scenes.push(scene);
+            },
+            SceneTransition::Pop => {
+                if let Some(mut top) = self.scenes.pop() {
+                    top.exit(&mut self.graphics, &mut self.params);
+                }
+                if let Some(top) = self.scenes.last_mut() {
+                    top.enter(&mut self.graphics, &mut self.params);
+                }
+            },
+            SceneTransition::Replace(mut scene) => {
+                if let Some(mut top) = self.scenes.pop() {
+                    top.exit(&mut self.graphics, &mut self.params);
+                }
+                scene.load(&mut self.graphics, &mut self.params);
+                scene.enter(&mut self.graphics, &mut self.params);
+                self.scenes.push(scene);
+            },
+            SceneTransition::Quit => {
+                self.quit();
+                return false;
+            },
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::input_map::InputBinding;
+
+    type Log = Vec<String>;
+
+    struct LoggedScene {
+        name: &'static str,
+    }
+
+    fn log(params: &mut Option<Log>, line: String) {
+        params.get_or_insert_with(Vec::new).push(line);
+    }
+
+    impl Scene<Log> for LoggedScene {
+        fn load(&mut self, _graphics: &mut Graphics, params: &mut Option<Log>) {
+            log(params, format!("load {}", self.name));
+        }
+        fn enter(&mut self, _graphics: &mut Graphics, params: &mut Option<Log>) {
+            log(params, format!("enter {}", self.name));
+        }
+        fn exit(&mut self, _graphics: &mut Graphics, params: &mut Option<Log>) {
+            log(params, format!("exit {}", self.name));
+        }
+        fn quit(&mut self, _graphics: &mut Graphics, params: &mut Option<Log>) {
+            log(params, format!("quit {}", self.name));
+        }
+    }
+
+    fn scene(name: &'static str) -> Box<dyn Scene<Log>> {
+        Box::new(LoggedScene { name })
+    }
+
+    fn take_log(game: &mut Game<Log>) -> Log {
+        std::mem::take(game.params.get_or_insert_with(Vec::new))
+    }
+
+    #[test]
+    fn scenes_stack_transitions() {
+        let mut game: Game<Log> = Game::new(Graphics::new_headless(32, 32).expect("headless graphics"));
+        game.set_params(Vec::new());
+
+        assert!(game.apply_transition(SceneTransition::Push(scene("title"))));
+        assert_eq!(take_log(&mut game), ["load title", "enter title"]);
+
+        assert!(game.apply_transition(SceneTransition::Push(scene("level"))));
+        assert_eq!(take_log(&mut game), ["exit title", "load level", "enter level"]);
+        assert_eq!(game.scenes.len(), 2);
+
+        assert!(game.apply_transition(SceneTransition::Replace(scene("boss"))));
+        assert_eq!(take_log(&mut game), ["exit level", "load boss", "enter boss"]);
+        assert_eq!(game.scenes.len(), 2);
+
+        assert!(game.apply_transition(SceneTransition::Pop));
+        assert_eq!(take_log(&mut game), ["exit boss", "enter title"]);
+        assert_eq!(game.scenes.len(), 1);
+
+        assert!(game.apply_transition(SceneTransition::None));
+        assert!(take_log(&mut game).is_empty());
+
+        assert!(game.apply_transition(SceneTransition::Push(scene("pause"))));
+        take_log(&mut game);
+        assert!(!game.apply_transition(SceneTransition::Quit));
+        assert_eq!(take_log(&mut game), ["quit pause", "quit title"]);
+    }
+
+    /*
+     * Updates and interpolation alpha of each frame (updates counted
+     * since the last draw)
+     */
+    fn updates_by_frame(tick_rate: Option<Fps>, max_update_steps: u32, fixed_dt: DeltaTime, frames: u64) -> Vec<(u32, Alpha)> {
+        let mut game: Game<(u32, Vec<(u32, Alpha)>)> = Game::new(Graphics::new_headless(32, 32).expect("headless graphics"));
+        game.set_params((0, Vec::new()))
+            .set_tick_rate(tick_rate)
+            .set_max_update_steps(max_update_steps)
+            .set_fixed_dt(Some(fixed_dt))
+            .set_callback_update(|_, params, _, _| {
+                if let Some((updates, _)) = params {
+                    *updates += 1;
+                }
+            })
+            .set_callback_draw_interpolated(|_, params, _, _, alpha| {
+                if let Some((updates, frames)) = params {
+                    frames.push((*updates, alpha));
+                    *updates = 0;
+                }
+            });
+        game.run_frames(frames, &mut Option::None);
+        game.params.take().map(|(_, frames)| frames).unwrap_or_default()
+    }
+
+    #[test]
+    fn fixed_timestep_steps_and_alpha() {
+        // Steps of 0.25s, frames of 0.375s
+        assert_eq!(updates_by_frame(Some(4.), 5, 0.375, 4), [(1, 0.5), (2, 0.), (1, 0.5), (2, 0.)]);
+        // Frames shorter than a step
+        assert_eq!(updates_by_frame(Some(4.), 5, 0.125, 3), [(0, 0.5), (1, 0.), (0, 0.5)]);
+    }
+
+    #[test]
+    fn fixed_timestep_max_update_steps() {
+        // 8.5 steps late : 3 updates, the other steps are dropped
+        assert_eq!(updates_by_frame(Some(4.), 3, 2.125, 2), [(3, 0.5), (3, 0.)]);
+    }
+
+    #[test]
+    fn variable_timestep_one_update_by_frame() {
+        assert_eq!(updates_by_frame(Option::None, 5, 0.375, 3), [(1, 1.), (1, 1.), (1, 1.)]);
+    }
+
+    /*
+     * Space just pressed in each update of a fixed timestep run (key
+     * down in the first frame)
+     */
+    fn space_pressed_by_update(tick_rate: Fps, fixed_dt: DeltaTime, frames: u64) -> Vec<bool> {
+        let mut recording = InputRecording::new();
+        recording.push(0, InputEvent::KeyDown { key: Some(Keys::Space), scancode: Some(Keys::Space), modifiers: Default::default() });
+
+        let mut game: Game<Vec<bool>> = Game::new(Graphics::new_headless(32, 32).expect("headless graphics"));
+        game.set_params(Vec::new())
+            .set_tick_rate(Some(tick_rate))
+            .set_fixed_dt(Some(fixed_dt))
+            .set_input_playback(Some(recording))
+            .set_callback_update(|_, params, inputs, _| {
+                params.get_or_insert_with(Vec::new).push(inputs.keyboard.is_just_pressed(&Keys::Space));
+            });
+        game.run_frames(frames, &mut Option::None);
+        game.params.take().unwrap_or_default()
+    }
+
+    #[test]
+    fn edges_kept_until_an_update() {
+        // 2 frames per update : the first frame has no update
+        assert_eq!(space_pressed_by_update(10., 0.05, 4), [true, false]);
+    }
+
+    #[test]
+    fn edges_seen_by_one_update() {
+        // 2 updates per frame
+        assert_eq!(space_pressed_by_update(10., 0.2, 2), [true, false, false, false]);
+    }
+
+    #[test]
+    fn input_map_kept_after_run() {
+        let mut input_map = InputMap::new();
+        input_map.bind("jump", InputBinding::Key(Keys::Space));
+
+        let mut game: Game<Log> = Game::new(Graphics::new_headless(32, 32).expect("headless graphics"));
+        game.set_input_map(input_map)
+            .set_callback_update(|_, _, inputs, _| {
+                inputs.map.bind("fire", InputBinding::Key(Keys::Enter));
+            });
+        game.run_frames(1, &mut Option::None);
+
+        assert_eq!(game.get_input_map().get_bindings("jump"), [InputBinding::Key(Keys::Space)]);
+        assert_eq!(game.get_input_map().get_bindings("fire"), [InputBinding::Key(Keys::Enter)]);
+    }
+
+    /*
+     * Space down and dt seen by each update
+     */
+    fn run_with_playback(game: &mut Game<Vec<(bool, DeltaTime)>>, recording: InputRecording, frames: u64) -> Vec<(bool, DeltaTime)> {
+        game.set_params(Vec::new())
+            .set_input_playback(Some(recording))
+            .set_callback_update(|_, params, inputs, dt| {
+                params.get_or_insert_with(Vec::new).push((inputs.keyboard.is_down(&Keys::Space), dt));
+            });
+        game.run_frames(frames, &mut Option::None);
+        game.params.take().unwrap_or_default()
+    }
+
+    #[test]
+    fn record_save_load_replay() {
+        let mut source = InputRecording::new();
+        source.push(1, InputEvent::KeyDown { key: Some(Keys::Space), scancode: Some(Keys::Space), modifiers: Default::default() });
+        source.push(3, InputEvent::KeyUp { key: Some(Keys::Space), scancode: Some(Keys::Space), modifiers: Default::default() });
+
+        // Record a run (headless : the events come from a playback)
+        let mut game = Game::new(Graphics::new_headless(32, 32).expect("headless graphics"));
+        game.set_fixed_dt(Some(0.25)).set_input_recording(true);
+        let recorded = run_with_playback(&mut game, source, 5);
+        assert_eq!(recorded, [(false, 0.25), (true, 0.25), (true, 0.25), (false, 0.25), (false, 0.25)]);
+
+        let filename = std::env::temp_dir().join(format!("game2d_replay_{}.txt", std::process::id()));
+        let filename = filename.to_string_lossy();
+        game.get_input_recording().unwrap().save(&filename).unwrap();
+        let loaded = InputRecording::load(&filename);
+        let _ = std::fs::remove_file(filename.as_ref());
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.get_fixed_dt(), Some(0.25));
+        assert_eq!(loaded.len(), 2);
+
+        // Replay without setting the dt : the one of the recording is used
+        let mut game = Game::new(Graphics::new_headless(32, 32).expect("headless graphics"));
+        assert_eq!(run_with_playback(&mut game, loaded, 5), recorded);
+    }
+
+    #[test]
+    fn pop_on_empty_stack_does_nothing() {
+        let mut game: Game<Log> = Game::new(Graphics::new_headless(32, 32).expect("headless graphics"));
+        assert!(game.apply_transition(SceneTransition::Pop));
+        assert!(game.scenes.is_empty());
+    }
+}
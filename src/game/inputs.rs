@@ -1,6 +1,127 @@
-use crate::inputs::{keyboard::Keyboard, mouse::Mouse};
+use crate::inputs::{gamepad::Gamepads, keyboard::Keyboard, mouse::Mouse, text::TextInput};
+use crate::inputs::event::InputEvent;
+use crate::inputs::input_map::{AxisValue, InputMap};
+use crate::inputs::text::TextEdit;
 
+use super::common::DeltaTime;
+
+#[derive(Default)]
 pub struct Inputs {
     pub keyboard: Keyboard,
     pub mouse: Mouse,
-}
\ No newline at end of file
+    pub gamepads: Gamepads,
+    pub text: TextInput,
+    pub map: InputMap,
+}
+
+impl Inputs {
+    /*
+     * apply()
+     * 
+     * @Brief : Update the inputs state with an event (SDL or playback)
+     */
+    pub fn apply(&mut self, event: &InputEvent) {
+        match event {
+            InputEvent::KeyDown { key, scancode, modifiers } => {
+                self.keyboard.set_modifiers(*modifiers);
+                if let Some(edit) = key.as_ref().and_then(|key| TextEdit::from_key(key, *modifiers)) {
+                    self.text.add_edit(edit);
+                }
+                self.keyboard.add_key_down(*key);
+                self.keyboard.add_scancode_down(*scancode);
+            },
+            InputEvent::KeyUp { key, scancode, modifiers } => {
+                self.keyboard.set_modifiers(*modifiers);
+                self.keyboard.add_key_up(*key);
+                self.keyboard.add_scancode_up(*scancode);
+            },
+            InputEvent::MouseMotion { x, y, xrel, yrel } => {
+                self.mouse.set_x(*x);
+                self.mouse.set_y(*y);
+                self.mouse.add_motion(*xrel, *yrel);
+            },
+            InputEvent::MouseButtonDown { button, x, y } => {
+                self.mouse.set_x(*x);
+                self.mouse.set_y(*y);
+                self.mouse.add_button_down(*button);
+            },
+            InputEvent::MouseButtonUp { button, x, y } => {
+                self.mouse.set_x(*x);
+                self.mouse.set_y(*y);
+                self.mouse.add_button_up(*button);
+            },
+            InputEvent::MouseWheel { x, y } => self.mouse.add_wheel(*x, *y),
+            InputEvent::TextInput(text) => self.text.add_text(text),
+            InputEvent::TextEditing { text, start, length } => self.text.set_composition(text, *start, *length),
+            InputEvent::GamepadConnected { id, name } => {
+                self.gamepads.connect(*id, name);
+            },
+            InputEvent::GamepadDisconnected(id) => self.gamepads.disconnect(*id),
+            InputEvent::GamepadButtonDown { id, button } => {
+                if let Some(player) = self.gamepads.get_player(*id) {
+                    self.gamepads.add_button_down(player, Some(*button));
+                }
+            },
+            InputEvent::GamepadButtonUp { id, button } => {
+                if let Some(player) = self.gamepads.get_player(*id) {
+                    self.gamepads.add_button_up(player, Some(*button));
+                }
+            },
+            InputEvent::GamepadAxis { id, axis, value } => {
+                if let Some(player) = self.gamepads.get_player(*id) {
+                    self.gamepads.set_axis(player, *axis, *value);
+                }
+            },
+            InputEvent::Quit => {},
+        }
+    }
+
+    /*
+     * next_frame()
+     *
+     * @Brief : Forget the edges (just pressed / released, motions, text)
+     *          once an update has seen them, held keys get older
+     */
+    pub fn next_frame(&mut self, dt: DeltaTime) {
+        self.keyboard.next_frame(dt);
+        self.mouse.next_frame(dt);
+        self.gamepads.next_frame();
+        self.text.next_frame();
+    }
+
+    /*
+     * is_action_down()
+     * 
+     * @Brief : One of the inputs bound to the action is down
+     */
+    pub fn is_action_down(&self, action: &str) -> bool {
+        self.map.is_down(self, action)
+    }
+
+    /*
+     * is_action_just_pressed()
+     * 
+     * @Brief : The action has been triggered during this frame
+     */
+    pub fn is_action_just_pressed(&self, action: &str) -> bool {
+        self.map.is_just_pressed(self, action)
+    }
+
+    /*
+     * is_action_just_released()
+     * 
+     * @Brief : The action has been released during this frame
+     */
+    pub fn is_action_just_released(&self, action: &str) -> bool {
+        self.map.is_just_released(self, action)
+    }
+
+    /*
+     * get_axis()
+     * 
+     * @Brief : Value of an axis between -1 and 1
+     */
+    pub fn get_axis(&self, axis: &str) -> AxisValue {
+        self.map.get_axis(self, axis)
+    }
+}
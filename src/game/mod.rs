@@ -1,4 +1,6 @@
+#[allow(clippy::module_inception)]
 pub mod game;
 pub mod common;
 pub mod inputs;
+pub mod scene;
 pub mod sprites;
\ No newline at end of file
@@ -0,0 +1,113 @@
+use crate::graphics::fonts::FontsManager;
+use crate::graphics::graphics::Graphics;
+use crate::graphics::window::WindowEvents;
+use crate::inputs::keyboard::Keys;
+use crate::inputs::mouse::MouseButtons;
+
+use super::common::{Alpha, DeltaTime, Position};
+use super::inputs::Inputs;
+
+/*
+ * SceneTransition
+ *
+ * @Brief : What to do with the scenes stack after a scene callback
+ */
+pub enum SceneTransition<T> {
+    None,
+    Push(Box<dyn Scene<T>>),
+    Pop,
+    Replace(Box<dyn Scene<T>>),
+    Quit,
+}
+
+/*
+ * Scene
+ *
+ * @Brief : A screen of the game (title, level, pause menu...).
+ *          Scenes are stacked in the Game, only the top scene receives
+ *          updates and keys.
+ */
+#[allow(unused_variables)]
+pub trait Scene<T> {
+
+    /*
+     * load()
+     *
+     * @Brief : Called once, when the scene is added to the stack
+     */
+    fn load(&mut self, graphics: &mut Graphics, params: &mut Option<T>) {}
+
+    /*
+     * enter()
+     *
+     * @Brief : Called each time the scene becomes the top of the stack
+     */
+    fn enter(&mut self, graphics: &mut Graphics, params: &mut Option<T>) {}
+
+    /*
+     * exit()
+     *
+     * @Brief : Called each time the scene stops being the top of the stack
+     */
+    fn exit(&mut self, graphics: &mut Graphics, params: &mut Option<T>) {}
+
+    /*
+     * update()
+     *
+     * @Brief : Called in each update when the scene is on top
+     */
+    fn update(&mut self, graphics: &mut Graphics, params: &mut Option<T>, inputs: &mut Inputs, dt: DeltaTime) -> SceneTransition<T> {
+        SceneTransition::None
+    }
+
+    /*
+     * draw()
+     *
+     * @Brief : Called in each draw
+     */
+    fn draw(&mut self, graphics: &mut Graphics, params: &mut Option<T>, inputs: &mut Inputs, fonts_manager: &mut Option<FontsManager>, alpha: Alpha) {}
+
+    /*
+     * key_pressed()
+     *
+     * @Brief : Called for each key pressed when the scene is on top
+     */
+    fn key_pressed(&mut self, graphics: &mut Graphics, params: &mut Option<T>, key: &Keys) -> SceneTransition<T> {
+        SceneTransition::None
+    }
+
+    /*
+     * mouse_pressed()
+     *
+     * @Brief : Called for each mouse button pressed when the scene is on top
+     */
+    fn mouse_pressed(&mut self, graphics: &mut Graphics, params: &mut Option<T>, button: &MouseButtons, x: Position, y: Position) -> SceneTransition<T> {
+        SceneTransition::None
+    }
+
+    /*
+     * window_event()
+     *
+     * @Brief : Called for each window event (resize, focus...) when the
+     *          scene is on top
+     */
+    fn window_event(&mut self, graphics: &mut Graphics, params: &mut Option<T>, event: &WindowEvents) -> SceneTransition<T> {
+        SceneTransition::None
+    }
+
+    /*
+     * quit()
+     *
+     * @Brief : Called before the game quits
+     */
+    fn quit(&mut self, graphics: &mut Graphics, params: &mut Option<T>) {}
+
+    /*
+     * draw_below()
+     *
+     * @Brief : Keep drawing the scenes under this one (overlays, pause menu...)
+     */
+    fn draw_below(&self) -> bool {
+        false
+    }
+}
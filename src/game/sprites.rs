@@ -13,6 +13,12 @@ pub struct Sprites {
     datas: HashMap<TypeId, Vec<Box<dyn Any>>>,
 }
 
+impl Default for Sprites {
+    fn default() -> Self {
+        Sprites::new()
+    }
+}
+
 impl Sprites {
     pub fn new() -> Self {
         Self { datas: HashMap::new() }
@@ -20,12 +26,7 @@ impl Sprites {
 
     pub fn add(&mut self, sprite: impl SpriteTrait + 'static) {
         let typeid = sprite.type_id();
-        if !self.datas.contains_key(&typeid) {
-            self.datas.insert(typeid, Vec::new());
-        }
-        if let Some(lst) = self.datas.get_mut(&typeid) {
-            lst.push(Box::new(sprite));
-        }
+        self.datas.entry(typeid).or_default().push(Box::new(sprite));
     }
 
     pub fn get<T: SpriteTrait + 'static>(&self, entity: T) -> Option<&Vec<Box<dyn Any>>> {
@@ -0,0 +1,351 @@
+/*================================================================
+ *                     B I T M A P   F O N T
+ *
+ * Fonts drawn from glyphs in images (pixel art) :
+ *  - AngelCode BMFont : .fnt file (text or XML) and its pages
+ *  - Grid : glyphs of the same size in an image, row after row
+ *
+ * The pages are loaded by the images manager of Graphics, the font
+ * is then used like a TTF one (set_font, print...) :
+ *
+ *   let font = graphics.load_bitmap_font(&mut fonts_manager, "fonts/pixel.fnt")?;
+ *   graphics.set_font(font);
+ *================================================================*/
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::error::{Game2dError, Game2dResult};
+use crate::game::common::Size;
+
+/*
+ * Glyph : where a char is in a page and how it's placed
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Glyph {
+    pub(crate) page: usize,
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    pub(crate) width: Size,
+    pub(crate) height: Size,
+    pub(crate) xoffset: i32,
+    pub(crate) yoffset: i32,
+    pub(crate) xadvance: i32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitmapFont {
+    // Images of the glyphs
+    pub(crate) pages: Vec<String>,
+    glyphs: HashMap<char, Glyph>,
+    kernings: HashMap<(char, char), i32>,
+    size: u16,
+    line_height: Size,
+}
+
+#[allow(dead_code)]
+impl BitmapFont {
+    /*
+     * from_fnt()
+     *
+     * @Brief : Read an AngelCode BMFont description (text or XML, the
+     *          pages are relative to the .fnt file)
+     */
+    pub(crate) fn from_fnt(filename: &str, content: &str) -> Game2dResult<BitmapFont> {
+        let bad = |message: String| Game2dError::Decode { path: filename.to_string(), message };
+        let directory = Path::new(filename).parent().unwrap_or_else(|| Path::new(""));
+
+        let mut font = BitmapFont {
+            pages: Vec::new(),
+            glyphs: HashMap::new(),
+            kernings: HashMap::new(),
+            size: 0,
+            line_height: 0,
+        };
+        let mut pages: Vec<(usize, String)> = Vec::new();
+
+        for (tag, attributes) in fnt_tags(content) {
+            let get = |name: &str| -> Game2dResult<i32> {
+                attributes
+                    .get(name)
+                    .ok_or_else(|| bad(format!("{} without {}", tag, name)))?
+                    .parse()
+                    .map_err(|_| bad(format!("Bad {} in {}", name, tag)))
+            };
+            let get_char = |name: &str| -> Game2dResult<char> {
+                char::from_u32(get(name)? as u32).ok_or_else(|| bad(format!("Bad char {} in {}", name, tag)))
+            };
+
+            match tag.as_str() {
+                "info" => font.size = get("size")?.unsigned_abs() as u16,
+                "common" => font.line_height = get("lineHeight")?.max(0) as Size,
+                "page" => {
+                    let file = attributes.get("file").ok_or_else(|| bad(String::from("page without file")))?;
+                    pages.push((get("id")? as usize, directory.join(file).to_string_lossy().to_string()));
+                },
+                "char" => {
+                    font.glyphs.insert(get_char("id")?, Glyph {
+                        page: get("page").unwrap_or(0).max(0) as usize,
+                        x: get("x")?,
+                        y: get("y")?,
+                        width: get("width")?.max(0) as Size,
+                        height: get("height")?.max(0) as Size,
+                        xoffset: get("xoffset")?,
+                        yoffset: get("yoffset")?,
+                        xadvance: get("xadvance")?,
+                    });
+                },
+                "kerning" => {
+                    font.kernings.insert((get_char("first")?, get_char("second")?), get("amount")?);
+                },
+                _ => {},
+            }
+        }
+
+        if pages.is_empty() || font.glyphs.is_empty() {
+            return Err(bad(String::from("Not a BMFont file (text or XML)")));
+        }
+
+        // The ids of the pages may not follow each other : the glyphs
+        // get the index of their page in font.pages
+        pages.sort();
+        let indexes: HashMap<usize, usize> = pages.iter().enumerate().map(|(index, (id, _))| (*id, index)).collect();
+        for glyph in font.glyphs.values_mut() {
+            glyph.page = *indexes
+                .get(&glyph.page)
+                .ok_or_else(|| bad(format!("Glyph in a missing page {}", glyph.page)))?;
+        }
+        font.pages = pages.into_iter().map(|(_, file)| file).collect();
+        Ok(font)
+    }
+
+    /*
+     * from_grid()
+     *
+     * @Brief : Glyphs of the same size in an image, in the order of
+     *          chars, left to right then top to bottom
+     */
+    pub(crate) fn from_grid(filename: &str, image_width: Size, glyph_width: Size, glyph_height: Size, chars: &str) -> Game2dResult<BitmapFont> {
+        if glyph_width == 0 || glyph_height == 0 || glyph_width > image_width {
+            return Err(Game2dError::Font(format!("Bad glyph size for {}", filename)));
+        }
+
+        let columns = image_width / glyph_width;
+        let glyphs = chars
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let (column, row) = (i as Size % columns, i as Size / columns);
+                (c, Glyph {
+                    page: 0,
+                    x: (column * glyph_width) as i32,
+                    y: (row * glyph_height) as i32,
+                    width: glyph_width,
+                    height: glyph_height,
+                    xoffset: 0,
+                    yoffset: 0,
+                    xadvance: glyph_width as i32,
+                })
+            })
+            .collect();
+
+        Ok(BitmapFont {
+            pages: vec![filename.to_string()],
+            glyphs,
+            kernings: HashMap::new(),
+            size: glyph_height as u16,
+            line_height: glyph_height,
+        })
+    }
+
+    pub fn get_size(&self) -> u16 {
+        self.size
+    }
+
+    pub fn get_line_height(&self) -> Size {
+        self.line_height
+    }
+
+    /*
+     * get_glyph()
+     *
+     * @Brief : Glyph of a char ('?' if the font hasn't the char)
+     */
+    pub(crate) fn get_glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs.get(&c).or_else(|| self.glyphs.get(&'?'))
+    }
+
+    pub(crate) fn get_kerning(&self, first: char, second: char) -> i32 {
+        self.kernings.get(&(first, second)).copied().unwrap_or(0)
+    }
+
+    /*
+     * measure()
+     *
+     * @Brief : Width of a line of text
+     */
+    pub(crate) fn measure(&self, text: &str) -> Size {
+        let mut width = 0;
+        let mut previous = Option::None;
+        for c in text.chars() {
+            if let Some(p) = previous {
+                width += self.get_kerning(p, c);
+            }
+            if let Some(glyph) = self.get_glyph(c) {
+                width += glyph.xadvance;
+            }
+            previous = Some(c);
+        }
+        width.max(0) as Size
+    }
+}
+
+/*
+ * fnt_tags()
+ *
+ * @Brief : Tags of a BMFont file with their attributes
+ *          ("char id=65 x=2" or "<char id="65" x="2"/>")
+ */
+fn fnt_tags(content: &str) -> Vec<(String, HashMap<String, String>)> {
+    let xml = content.trim_start().starts_with('<');
+    let elements: Vec<&str> = match xml {
+        true => content.split('<').collect(),
+        false => content.lines().collect(),
+    };
+
+    elements
+        .into_iter()
+        .map(|e| e.trim().trim_end_matches('>').trim_end_matches('/').trim())
+        .filter(|e| !e.is_empty() && !e.starts_with('/') && !e.starts_with('?') && !e.starts_with('!'))
+        .map(|e| {
+            let (tag, rest) = e.split_once(char::is_whitespace).unwrap_or((e, ""));
+            (tag.to_string(), fnt_attributes(rest))
+        })
+        .collect()
+}
+
+/*
+ * fnt_attributes()
+ *
+ * @Brief : key=value pairs (values may be quoted and contain spaces)
+ */
+fn fnt_attributes(text: &str) -> HashMap<String, String> {
+    let mut attributes = HashMap::new();
+    let mut rest = text.trim_start();
+
+    while let Some((key, after)) = rest.split_once('=') {
+        let key = key.trim().to_string();
+        let after = after.trim_start();
+        let (value, next) = match after.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => after.split_once(char::is_whitespace).unwrap_or((after, "")),
+        };
+        attributes.insert(key, value.to_string());
+        rest = next.trim_start();
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT_FNT: &str = "\
+info face=\"Pixel font\" size=-8 bold=0
+common lineHeight=10 base=8 pages=2
+page id=0 file=\"pixel_0.png\"
+page id=2 file=\"pixel_2.png\"
+chars count=3
+char id=65 x=0 y=0 width=6 height=8 xoffset=0 yoffset=1 xadvance=7 page=0
+char id=86 x=6 y=0 width=6 height=8 xoffset=1 yoffset=1 xadvance=7 page=2
+char id=63 x=0 y=8 width=5 height=8 xoffset=0 yoffset=1 xadvance=6 page=0
+kernings count=1
+kerning first=65 second=86 amount=-2
+";
+
+    const XML_FNT: &str = r#"<?xml version="1.0"?>
+<font>
+  <info face="Pixel font" size="8"/>
+  <common lineHeight="9" base="7" pages="1"/>
+  <pages>
+    <page id="0" file="pixel.png"/>
+  </pages>
+  <chars count="1">
+    <char id="66" x="3" y="4" width="5" height="7" xoffset="0" yoffset="2" xadvance="6" page="0"/>
+  </chars>
+</font>
+"#;
+
+    fn page(file: &str) -> String {
+        Path::new("fonts").join(file).to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn fnt_text_pages_by_id() {
+        let font = BitmapFont::from_fnt("fonts/pixel.fnt", TEXT_FNT).unwrap();
+        assert_eq!(font.get_size(), 8);
+        assert_eq!(font.get_line_height(), 10);
+        assert_eq!(font.pages, vec![page("pixel_0.png"), page("pixel_2.png")]);
+
+        // Page id 2 is the second page
+        let v = font.get_glyph('V').unwrap();
+        assert_eq!(font.pages[v.page], page("pixel_2.png"));
+        assert_eq!((v.x, v.y, v.width, v.height, v.xoffset), (6, 0, 6, 8, 1));
+        assert_eq!(font.pages[font.get_glyph('A').unwrap().page], page("pixel_0.png"));
+
+        // Unknown chars are drawn with '?'
+        assert_eq!(font.get_glyph('Z'), font.get_glyph('?'));
+    }
+
+    #[test]
+    fn fnt_xml() {
+        let font = BitmapFont::from_fnt("fonts/pixel.fnt", XML_FNT).unwrap();
+        assert_eq!(font.get_size(), 8);
+        assert_eq!(font.get_line_height(), 9);
+        assert_eq!(font.pages, vec![page("pixel.png")]);
+        assert_eq!(font.get_glyph('B'), Some(&Glyph {
+            page: 0, x: 3, y: 4, width: 5, height: 7, xoffset: 0, yoffset: 2, xadvance: 6,
+        }));
+        // No '?' to fall back on
+        assert_eq!(font.get_glyph('Z'), Option::None);
+    }
+
+    #[test]
+    fn fnt_errors() {
+        assert!(BitmapFont::from_fnt("bad.fnt", "").is_err());
+        assert!(BitmapFont::from_fnt("bad.fnt", "page id=0 file=\"a.png\"\n").is_err());
+        let missing_page = "page id=0 file=\"a.png\"\nchar id=65 x=0 y=0 width=1 height=1 xoffset=0 yoffset=0 xadvance=1 page=1\n";
+        assert!(BitmapFont::from_fnt("bad.fnt", missing_page).is_err());
+        let bad_value = "page id=0 file=\"a.png\"\nchar id=65 x=zero y=0 width=1 height=1 xoffset=0 yoffset=0 xadvance=1\n";
+        assert!(BitmapFont::from_fnt("bad.fnt", bad_value).is_err());
+    }
+
+    #[test]
+    fn grid_positions() {
+        // 3 columns of 4x5 glyphs
+        let font = BitmapFont::from_grid("pixel.png", 12, 4, 5, "ABCDE").unwrap();
+        assert_eq!(font.pages, vec![String::from("pixel.png")]);
+        assert_eq!(font.get_line_height(), 5);
+
+        let positions: Vec<(i32, i32)> = "ABCDE".chars().map(|c| {
+            let glyph = font.get_glyph(c).unwrap();
+            (glyph.x, glyph.y)
+        }).collect();
+        assert_eq!(positions, [(0, 0), (4, 0), (8, 0), (0, 5), (4, 5)]);
+        assert_eq!(font.measure("ACE"), 12);
+
+        assert!(BitmapFont::from_grid("pixel.png", 12, 0, 5, "A").is_err());
+        assert!(BitmapFont::from_grid("pixel.png", 12, 16, 5, "A").is_err());
+    }
+
+    #[test]
+    fn measure_with_kerning() {
+        let font = BitmapFont::from_fnt("fonts/pixel.fnt", TEXT_FNT).unwrap();
+        assert_eq!(font.measure(""), 0);
+        assert_eq!(font.measure("A"), 7);
+        // 7 + 7 - 2
+        assert_eq!(font.measure("AV"), 12);
+        assert_eq!(font.measure("VA"), 14);
+        // 'Z' is measured as '?'
+        assert_eq!(font.measure("AZ"), 13);
+    }
+}
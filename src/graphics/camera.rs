@@ -0,0 +1,452 @@
+/*================================================================
+ *                          C A M E R A
+ *
+ * 2D camera : view of the world (position, zoom, rotation) with
+ * smooth following, world bounds and screen shake. Attach it to
+ * Graphics to draw in world space :
+ *
+ *   camera.follow(player.x, player.y);
+ *   camera.update(dt);
+ *   graphics.attach_camera(&camera);
+ *   ... world ...
+ *   graphics.detach_camera();
+ *   ... HUD ...
+ *================================================================*/
+use crate::game::common::{Angle, DeltaTime, Position, Position2d, Transform2d, Transformation};
+
+pub const CAMERA_DEFAULT_SHAKE_OFFSET: Position = 16.;
+pub const CAMERA_DEFAULT_SHAKE_ANGLE: Angle = 5.;
+pub const CAMERA_DEFAULT_TRAUMA_DECAY: Transformation = 1.;
+
+/*
+ * WorldBounds : area of the world the camera can show
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldBounds {
+    pub x: Position,
+    pub y: Position,
+    pub width: Position,
+    pub height: Position,
+}
+
+#[derive(Clone, Debug)]
+pub struct Camera2D {
+    // Size of the view on the screen
+    width: Position,
+    height: Position,
+
+    // Center of the view in the world
+    position: Position2d,
+    zoom: Transformation,
+    rotation: Angle,
+
+    // Follow
+    target: Option<Position2d>,
+    follow_speed: Option<Transformation>,
+    dead_zone: (Position, Position),
+
+    bounds: Option<WorldBounds>,
+
+    // Shake (trauma between 0 and 1, shake = trauma²)
+    trauma: Transformation,
+    trauma_decay: Transformation,
+    shake_offset: Position,
+    shake_angle: Angle,
+    shake_time: DeltaTime,
+}
+
+#[allow(dead_code)]
+impl Camera2D {
+    /*
+     * new()
+     *
+     * @Brief : Camera for a view of the size of the screen (logical
+     *          size if any), looking at the center of this size
+     */
+    pub fn new(width: Position, height: Position) -> Camera2D {
+        Camera2D {
+            width,
+            height,
+            position: Position2d { x: width / 2., y: height / 2. },
+            zoom: 1.,
+            rotation: 0.,
+            target: Option::None,
+            follow_speed: Option::None,
+            dead_zone: (0., 0.),
+            bounds: Option::None,
+            trauma: 0.,
+            trauma_decay: CAMERA_DEFAULT_TRAUMA_DECAY,
+            shake_offset: CAMERA_DEFAULT_SHAKE_OFFSET,
+            shake_angle: CAMERA_DEFAULT_SHAKE_ANGLE,
+            shake_time: 0.,
+        }
+    }
+
+    //=======================================================================
+    //                               VIEW
+    //=======================================================================
+    pub fn set_size(&mut self, width: Position, height: Position) -> &mut Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /*
+     * set_position()
+     *
+     * @Brief : Look at a position of the world (center of the view)
+     */
+    pub fn set_position(&mut self, x: Position, y: Position) -> &mut Self {
+        self.position = Position2d { x, y };
+        self.clamp_to_bounds();
+        self
+    }
+
+    pub fn get_position(&self) -> Position2d {
+        self.position
+    }
+
+    /*
+     * set_zoom()
+     *
+     * @Brief : Zoom (2 : the world looks twice bigger)
+     */
+    pub fn set_zoom(&mut self, zoom: Transformation) -> &mut Self {
+        self.zoom = zoom.max(0.01);
+        self.clamp_to_bounds();
+        self
+    }
+
+    pub fn get_zoom(&self) -> Transformation {
+        self.zoom
+    }
+
+    /*
+     * set_rotation()
+     *
+     * @Brief : Rotation of the view (degrees)
+     */
+    pub fn set_rotation(&mut self, rotation: Angle) -> &mut Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn get_rotation(&self) -> Angle {
+        self.rotation
+    }
+
+    /*
+     * set_bounds()
+     *
+     * @Brief : Keep the view inside the world (None : no limit)
+     */
+    pub fn set_bounds(&mut self, bounds: Option<WorldBounds>) -> &mut Self {
+        self.bounds = bounds;
+        self.clamp_to_bounds();
+        self
+    }
+
+    //=======================================================================
+    //                              FOLLOW
+    //=======================================================================
+    /*
+     * follow()
+     *
+     * @Brief : Set the position to follow (call it each update)
+     */
+    pub fn follow(&mut self, x: Position, y: Position) -> &mut Self {
+        self.target = Some(Position2d { x, y });
+        self
+    }
+
+    pub fn stop_following(&mut self) -> &mut Self {
+        self.target = Option::None;
+        self
+    }
+
+    /*
+     * set_follow_speed()
+     *
+     * @Brief : How fast the camera catches up the target (higher is
+     *          faster, None : at once)
+     */
+    pub fn set_follow_speed(&mut self, follow_speed: Option<Transformation>) -> &mut Self {
+        self.follow_speed = follow_speed;
+        self
+    }
+
+    /*
+     * set_dead_zone()
+     *
+     * @Brief : Size of the area around the center (world units) where
+     *          the target moves without moving the camera
+     */
+    pub fn set_dead_zone(&mut self, width: Position, height: Position) -> &mut Self {
+        self.dead_zone = (width.max(0.), height.max(0.));
+        self
+    }
+
+    //=======================================================================
+    //                               SHAKE
+    //=======================================================================
+    /*
+     * add_trauma()
+     *
+     * @Brief : Shake the camera (0..1, added to the current trauma)
+     */
+    pub fn add_trauma(&mut self, trauma: Transformation) -> &mut Self {
+        self.trauma = (self.trauma + trauma).clamp(0., 1.);
+        self
+    }
+
+    pub fn get_trauma(&self) -> Transformation {
+        self.trauma
+    }
+
+    /*
+     * set_shake()
+     *
+     * @Brief : Max offset (screen units) and angle (degrees) of the
+     *          shake, and trauma lost per second
+     */
+    pub fn set_shake(&mut self, offset: Position, angle: Angle, trauma_decay: Transformation) -> &mut Self {
+        self.shake_offset = offset;
+        self.shake_angle = angle;
+        self.trauma_decay = trauma_decay.max(0.);
+        self
+    }
+
+    //=======================================================================
+    //                              UPDATE
+    //=======================================================================
+    /*
+     * update()
+     *
+     * @Brief : Move toward the target and fade the shake
+     */
+    pub fn update(&mut self, dt: DeltaTime) {
+        if let Some(target) = self.target {
+            // Position where the target is back in the dead zone
+            let desired = Position2d {
+                x: follow_axis(self.position.x, target.x, self.dead_zone.0 / 2.),
+                y: follow_axis(self.position.y, target.y, self.dead_zone.1 / 2.),
+            };
+
+            match self.follow_speed {
+                Some(speed) => {
+                    let t = 1. - (-speed * dt).exp();
+                    self.position.x += (desired.x - self.position.x) * t;
+                    self.position.y += (desired.y - self.position.y) * t;
+                },
+                None => self.position = desired,
+            }
+            self.clamp_to_bounds();
+        }
+
+        self.trauma = (self.trauma - self.trauma_decay * dt).max(0.);
+        self.shake_time += dt;
+    }
+
+    /*
+     * clamp_to_bounds()
+     *
+     * @Brief : Keep the view inside the bounds (centered if the
+     *          bounds are smaller than the view)
+     */
+    fn clamp_to_bounds(&mut self) {
+        if let Some(bounds) = self.bounds {
+            let (half_w, half_h) = (self.width / self.zoom / 2., self.height / self.zoom / 2.);
+            self.position.x = clamp_or_center(self.position.x, bounds.x + half_w, bounds.x + bounds.width - half_w);
+            self.position.y = clamp_or_center(self.position.y, bounds.y + half_h, bounds.y + bounds.height - half_h);
+        }
+    }
+
+    //=======================================================================
+    //                            TRANSFORM
+    //=======================================================================
+    /*
+     * get_transform()
+     *
+     * @Brief : World to screen transformation (shake included)
+     */
+    pub fn get_transform(&self) -> Transform2d {
+        let shake = self.trauma * self.trauma;
+        let offset_x = self.shake_offset * shake * noise(1., self.shake_time);
+        let offset_y = self.shake_offset * shake * noise(2., self.shake_time);
+        let angle = self.rotation + self.shake_angle * (shake * noise(3., self.shake_time)) as Angle;
+
+        Transform2d::translation(-self.position.x, -self.position.y)
+            .then(&Transform2d::scaling(self.zoom, self.zoom))
+            .then(&Transform2d::rotation(-angle))
+            .then(&Transform2d::translation(self.width / 2. + offset_x, self.height / 2. + offset_y))
+    }
+
+    /*
+     * world_to_screen()
+     *
+     * @Brief : Screen position of a world position
+     */
+    pub fn world_to_screen(&self, x: Position, y: Position) -> (Position, Position) {
+        self.get_transform().apply(x, y)
+    }
+
+    /*
+     * screen_to_world()
+     *
+     * @Brief : World position of a screen position (mouse picking)
+     */
+    pub fn screen_to_world(&self, x: Position, y: Position) -> (Position, Position) {
+        match self.get_transform().inverse() {
+            Some(inverse) => inverse.apply(x, y),
+            None => (x, y),
+        }
+    }
+}
+
+/*
+ * follow_axis()
+ *
+ * @Brief : Center moved just enough to have the target in the dead zone
+ */
+fn follow_axis(center: Position, target: Position, half_dead_zone: Position) -> Position {
+    if target < center - half_dead_zone {
+        target + half_dead_zone
+    } else if target > center + half_dead_zone {
+        target - half_dead_zone
+    } else {
+        center
+    }
+}
+
+fn clamp_or_center(value: Position, min: Position, max: Position) -> Position {
+    if min > max { (min + max) / 2. } else { value.clamp(min, max) }
+}
+
+/*
+ * noise()
+ *
+ * @Brief : Smooth pseudo random value between -1 and 1
+ */
+fn noise(seed: Transformation, time: DeltaTime) -> Transformation {
+    let t = time * 25. + seed * 17.;
+    ((t.sin() + (t * 2.3 + seed).sin() * 0.5 + (t * 5.1 + seed * 3.).sin() * 0.25) / 1.75).clamp(-1., 1.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: (Position, Position), expected: (Position, Position)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-3 && (actual.1 - expected.1).abs() < 1e-3,
+            "{:?} instead of {:?}", actual, expected
+        );
+    }
+
+    fn position(camera: &Camera2D) -> (Position, Position) {
+        (camera.get_position().x, camera.get_position().y)
+    }
+
+    #[test]
+    fn world_to_screen_with_zoom_and_rotation() {
+        let mut camera = Camera2D::new(320., 180.);
+        camera.set_position(100., 50.).set_zoom(2.);
+
+        // The position looked at is the center of the screen
+        assert_near(camera.world_to_screen(100., 50.), (160., 90.));
+        assert_near(camera.world_to_screen(110., 45.), (180., 80.));
+
+        // A quarter turn : the world x axis goes up on the screen
+        camera.set_rotation(90.);
+        assert_near(camera.world_to_screen(100., 50.), (160., 90.));
+        assert_near(camera.world_to_screen(110., 50.), (160., 70.));
+    }
+
+    #[test]
+    fn screen_to_world_is_the_inverse() {
+        let mut camera = Camera2D::new(320., 180.);
+        camera.set_position(-40., 75.).set_zoom(0.75).set_rotation(-33.);
+
+        for (x, y) in [(0., 0.), (160., 90.), (319., 12.), (-50., 400.)] {
+            let (wx, wy) = camera.screen_to_world(x, y);
+            assert_near(camera.world_to_screen(wx, wy), (x, y));
+        }
+        for (x, y) in [(-40., 75.), (1000., -3.)] {
+            let (sx, sy) = camera.world_to_screen(x, y);
+            assert_near(camera.screen_to_world(sx, sy), (x, y));
+        }
+    }
+
+    #[test]
+    fn follow_dead_zone() {
+        let mut camera = Camera2D::new(320., 180.);
+        camera.set_position(100., 50.).set_dead_zone(40., 20.);
+
+        // Inside the dead zone : no move
+        camera.follow(110., 55.).update(0.1);
+        assert_near(position(&camera), (100., 50.));
+
+        // Just enough to get the target back on the edge of the zone
+        camera.follow(130., 20.).update(0.1);
+        assert_near(position(&camera), (110., 30.));
+
+        // Not followed anymore
+        camera.stop_following().update(0.1);
+        assert_near(position(&camera), (110., 30.));
+    }
+
+    #[test]
+    fn follow_speed() {
+        let mut camera = Camera2D::new(320., 180.);
+        camera.set_position(0., 0.).set_follow_speed(Some(2.)).follow(100., 0.);
+
+        camera.update(0.5);
+        // 1 - e^-1 of the way
+        assert_near(position(&camera), (100. * (1. - (-1f32).exp()), 0.));
+        for _ in 0..100 {
+            camera.update(0.5);
+        }
+        assert_near(position(&camera), (100., 0.));
+    }
+
+    #[test]
+    fn bounds_clamping() {
+        let mut camera = Camera2D::new(320., 180.);
+        camera.set_bounds(Some(WorldBounds { x: 0., y: 0., width: 400., height: 300. }));
+
+        camera.set_position(0., 0.);
+        assert_near(position(&camera), (160., 90.));
+        camera.set_position(1000., 1000.);
+        assert_near(position(&camera), (240., 210.));
+
+        // Followed target near a corner
+        camera.follow(390., -20.).update(0.1);
+        assert_near(position(&camera), (240., 90.));
+
+        // Zoomed in : the view is smaller
+        camera.set_zoom(2.).set_position(0., 0.);
+        assert_near(position(&camera), (80., 45.));
+
+        // Zoomed out : the view is bigger than the bounds, centered
+        camera.set_zoom(0.5);
+        assert_near(position(&camera), (200., 150.));
+    }
+
+    #[test]
+    fn trauma_fades() {
+        let mut camera = Camera2D::new(320., 180.);
+        camera.set_shake(10., 0., 0.5);
+        camera.add_trauma(0.75).add_trauma(0.75);
+        assert_eq!(camera.get_trauma(), 1.);
+        camera.update(1.);
+        assert_eq!(camera.get_trauma(), 0.5);
+
+        // Shaken by less than the max offset
+        let (x, y) = camera.world_to_screen(160., 90.);
+        assert!((x - 160.).abs() <= 10. * 0.25 && (y - 90.).abs() <= 10. * 0.25);
+
+        camera.update(2.);
+        assert_eq!(camera.get_trauma(), 0.);
+        assert_near(camera.world_to_screen(160., 90.), (160., 90.));
+    }
+}
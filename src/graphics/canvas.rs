@@ -0,0 +1,332 @@
+/*================================================================
+ *                       S D L   C A N V A S
+ *
+ * SDL renderer : a window canvas (hardware) or a surface canvas
+ * (software, in-memory, no display needed).
+ *================================================================*/
+use std::os::raw::c_int;
+
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::image::LoadTexture;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::{BlendMode, Canvas, Texture, TextureCreator};
+use sdl2::surface::{Surface, SurfaceContext};
+use sdl2::sys::{SDL_Point, SDL_Rect, SDL_Renderer, SDL_RendererFlip, SDL_Texture};
+use sdl2::video::{Window, WindowContext};
+
+use crate::error::{Game2dError, Game2dResult};
+use crate::game::common::Size;
+use super::color::Color;
+use super::graphics::DrawMode;
+use super::renderer::{Renderer, TextureCopy};
+
+/*
+ * gfx_coordinate()
+ *
+ * @Brief : Coordinate for the SDL gfx primitives (i16), clamped so that
+ *          far off-screen points stay off-screen instead of wrapping around
+ */
+fn gfx_coordinate(v: i32) -> i16 {
+    v.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+}
+
+macro_rules! with_canvas {
+    ($self:expr, $canvas:ident => $body:expr) => {
+        match $self {
+            SdlCanvas::Window($canvas) => $body,
+            SdlCanvas::Surface($canvas) => $body,
+        }
+    };
+}
+
+macro_rules! with_creator {
+    ($self:expr, $creator:ident => $body:expr) => {
+        match $self {
+            TexturesCreator::Window($creator) => $body,
+            TexturesCreator::Surface($creator) => $body,
+        }
+    };
+}
+
+pub enum SdlCanvas {
+    Window(Canvas<Window>),
+    Surface(Canvas<Surface<'static>>),
+}
+
+impl SdlCanvas {
+    /*
+     * new_headless()
+     *
+     * @Brief : Create a software canvas rendering in a surface
+     */
+    pub fn new_headless(width: Size, height: Size) -> Game2dResult<SdlCanvas> {
+        let surface = Surface::new(width, height, PixelFormatEnum::RGBA32).map_err(Game2dError::Init)?;
+        let mut canvas = SdlCanvas::Surface(surface.into_canvas().map_err(Game2dError::Init)?);
+        canvas.set_blend_mode(BlendMode::Blend);
+        Ok(canvas)
+    }
+
+    /*
+     * from_window()
+     *
+     * @Brief : Create a canvas rendering in a window
+     */
+    pub fn from_window(canvas: Canvas<Window>) -> SdlCanvas {
+        let mut canvas = SdlCanvas::Window(canvas);
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas
+    }
+
+    fn set_blend_mode(&mut self, blend: BlendMode) {
+        with_canvas!(self, c => c.set_blend_mode(blend))
+    }
+}
+
+impl Renderer for SdlCanvas {
+    fn clear(&mut self, color: Color) {
+        with_canvas!(self, c => {
+            c.set_draw_color(color.to_sdl_color());
+            c.clear()
+        })
+    }
+
+    fn present(&mut self) {
+        with_canvas!(self, c => c.present())
+    }
+
+    fn line(&mut self, start: Point, end: Point, color: Color) -> Game2dResult<()> {
+        with_canvas!(self, c => {
+            c.set_draw_color(color.to_sdl_color());
+            c.draw_line(start, end)
+        }).map_err(Game2dError::Render)
+    }
+
+    fn rectangle(&mut self, mode: DrawMode, rect: Rect, color: Color) -> Game2dResult<()> {
+        with_canvas!(self, c => {
+            c.set_draw_color(color.to_sdl_color());
+            match mode {
+                DrawMode::Fill => c.fill_rect(rect),
+                DrawMode::Line => c.draw_rect(rect),
+            }
+        }).map_err(Game2dError::Render)
+    }
+
+    fn polygon(&mut self, mode: DrawMode, points: &[Point], color: Color) -> Game2dResult<()> {
+        if points.is_empty() {
+            return Ok(());
+        }
+        with_canvas!(self, c => match mode {
+            DrawMode::Fill => {
+                let vx: Vec<i16> = points.iter().map(|p| gfx_coordinate(p.x())).collect();
+                let vy: Vec<i16> = points.iter().map(|p| gfx_coordinate(p.y())).collect();
+                c.filled_polygon(&vx, &vy, color.to_sdl_color())
+            },
+            DrawMode::Line => {
+                let mut closed = points.to_vec();
+                closed.push(points[0]);
+                c.set_draw_color(color.to_sdl_color());
+                c.draw_lines(closed.as_slice())
+            },
+        }).map_err(Game2dError::Render)
+    }
+
+    fn thick_line(&mut self, start: Point, end: Point, width: u8, color: Color) -> Game2dResult<()> {
+        with_canvas!(self, c => c.thick_line(
+            gfx_coordinate(start.x()),
+            gfx_coordinate(start.y()),
+            gfx_coordinate(end.x()),
+            gfx_coordinate(end.y()),
+            width.max(1),
+            color.to_sdl_color(),
+        )).map_err(Game2dError::Render)
+    }
+
+    fn aa_line(&mut self, start: Point, end: Point, color: Color) -> Game2dResult<()> {
+        with_canvas!(self, c => c.aa_line(
+            gfx_coordinate(start.x()),
+            gfx_coordinate(start.y()),
+            gfx_coordinate(end.x()),
+            gfx_coordinate(end.y()),
+            color.to_sdl_color(),
+        )).map_err(Game2dError::Render)
+    }
+
+    fn aa_polygon(&mut self, points: &[Point], color: Color) -> Game2dResult<()> {
+        if points.is_empty() {
+            return Ok(());
+        }
+        let vx: Vec<i16> = points.iter().map(|p| gfx_coordinate(p.x())).collect();
+        let vy: Vec<i16> = points.iter().map(|p| gfx_coordinate(p.y())).collect();
+        with_canvas!(self, c => c.aa_polygon(&vx, &vy, color.to_sdl_color())).map_err(Game2dError::Render)
+    }
+
+    fn texture(&mut self, _name: &str, copy: &TextureCopy) -> Game2dResult<()> {
+        if copy.flip_horizontal && copy.flip_vertical {
+            return with_canvas!(self, c => copy_flipped(c.raw(), copy));
+        }
+        with_canvas!(self, c => c.copy_ex(
+            copy.texture,
+            copy.src,
+            copy.dst,
+            copy.angle,
+            copy.center,
+            copy.flip_horizontal,
+            copy.flip_vertical,
+        )).map_err(Game2dError::Render)
+    }
+
+    fn set_target(&mut self, target: Option<(&str, &Texture)>) -> Game2dResult<()> {
+        with_canvas!(self, c => {
+            if !c.render_target_supported() {
+                return Err(Game2dError::Render(String::from("Render targets not supported")));
+            }
+            let texture = target.map_or(std::ptr::null_mut(), |(_, t)| t.raw());
+            // SAFETY : the texture is kept by the images manager of Graphics
+            match unsafe { sdl2::sys::SDL_SetRenderTarget(c.raw(), texture) } {
+                0 => Ok(()),
+                _ => Err(Game2dError::Render(sdl2::get_error())),
+            }
+        })
+    }
+
+    fn textures_creator(&self) -> TexturesCreator {
+        match self {
+            SdlCanvas::Window(c) => TexturesCreator::Window(c.texture_creator()),
+            SdlCanvas::Surface(c) => TexturesCreator::Surface(c.texture_creator()),
+        }
+    }
+
+    fn output_size(&self) -> Game2dResult<(Size, Size)> {
+        with_canvas!(self, c => c.output_size()).map_err(Game2dError::Render)
+    }
+
+    fn read_pixels(&mut self) -> Game2dResult<Vec<u8>> {
+        // SDL reads in the viewport only : read all the output
+        with_canvas!(self, c => {
+            let viewport = c.viewport();
+            let (sx, sy) = c.scale();
+            c.set_scale(1., 1.).map_err(Game2dError::Render)?;
+            c.set_viewport(None);
+            let pixels = c.read_pixels(None, PixelFormatEnum::RGBA32).map_err(Game2dError::Render);
+            c.set_scale(sx, sy).map_err(Game2dError::Render)?;
+            c.set_viewport(viewport);
+            pixels
+        })
+    }
+
+    fn set_view(&mut self, viewport: Option<Rect>, sx: f32, sy: f32) -> Game2dResult<()> {
+        // SDL viewports are in scaled coordinates
+        with_canvas!(self, c => {
+            c.set_scale(sx, sy).map_err(Game2dError::Render)?;
+            c.set_viewport(viewport.map(|v| Rect::new(
+                (v.x() as f32 / sx) as i32,
+                (v.y() as f32 / sy) as i32,
+                (v.width() as f32 / sx).round() as u32,
+                (v.height() as f32 / sy).round() as u32,
+            )));
+            Ok(())
+        })
+    }
+
+    fn window(&self) -> Option<&Window> {
+        match self {
+            SdlCanvas::Window(c) => Some(c.window()),
+            SdlCanvas::Surface(_) => Option::None,
+        }
+    }
+
+    fn window_mut(&mut self) -> Option<&mut Window> {
+        match self {
+            SdlCanvas::Window(c) => Some(c.window_mut()),
+            SdlCanvas::Surface(_) => Option::None,
+        }
+    }
+}
+
+extern "C" {
+    // Same function as sdl2::sys::SDL_RenderCopyEx with the flip as flags :
+    // SDL_RendererFlip is an enum there, without the value of both flips
+    #[link_name = "SDL_RenderCopyEx"]
+    fn SDL_RenderCopyEx_flags(
+        renderer: *mut SDL_Renderer,
+        texture: *mut SDL_Texture,
+        srcrect: *const SDL_Rect,
+        dstrect: *const SDL_Rect,
+        angle: f64,
+        center: *const SDL_Point,
+        flip: u32,
+    ) -> c_int;
+}
+
+/*
+ * copy_flipped()
+ *
+ * @Brief : Copy a texture flipped on both axes (Canvas::copy_ex of
+ *          sdl2 0.35 builds an invalid SDL_RendererFlip for it)
+ */
+fn copy_flipped(renderer: *mut SDL_Renderer, copy: &TextureCopy) -> Game2dResult<()> {
+    let flip = SDL_RendererFlip::SDL_FLIP_HORIZONTAL as u32 | SDL_RendererFlip::SDL_FLIP_VERTICAL as u32;
+    // SAFETY : the renderer and the texture are alive during the call,
+    //          the rects and the point are read only
+    let result = unsafe {
+        SDL_RenderCopyEx_flags(
+            renderer,
+            copy.texture.raw(),
+            copy.src.as_ref().map_or(std::ptr::null(), |r| r.raw()),
+            copy.dst.raw(),
+            copy.angle,
+            copy.center.as_ref().map_or(std::ptr::null(), |p| p.raw()),
+            flip,
+        )
+    };
+    match result {
+        0 => Ok(()),
+        _ => Err(Game2dError::Render(sdl2::get_error())),
+    }
+}
+
+/*================================================================
+ *                   T E X T U R E S   C R E A T O R
+ *================================================================*/
+pub enum TexturesCreator {
+    Window(TextureCreator<WindowContext>),
+    Surface(TextureCreator<SurfaceContext<'static>>),
+}
+
+impl TexturesCreator {
+    pub(crate) fn load_texture(&self, filename: &str) -> Game2dResult<Texture> {
+        with_creator!(self, t => t.load_texture(filename)).map_err(|e| Game2dError::from_asset(filename, e))
+    }
+
+    /*
+     * create_texture_target()
+     *
+     * @Brief : Create a texture to draw in (render target)
+     */
+    pub(crate) fn create_texture_target(&self, width: Size, height: Size) -> Game2dResult<Texture> {
+        let mut texture = with_creator!(self, t => t.create_texture_target(PixelFormatEnum::RGBA8888, width, height))
+            .map_err(|e| Game2dError::Render(e.to_string()))?;
+        texture.set_blend_mode(BlendMode::Blend);
+        Ok(texture)
+    }
+
+    pub(crate) fn create_texture_from_surface(&self, surface: &Surface) -> Game2dResult<Texture> {
+        with_creator!(self, t => t.create_texture_from_surface(surface)).map_err(|e| Game2dError::Render(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gfx_coordinate_clamped() {
+        assert_eq!(gfx_coordinate(0), 0);
+        assert_eq!(gfx_coordinate(-120), -120);
+        assert_eq!(gfx_coordinate(32767), i16::MAX);
+        assert_eq!(gfx_coordinate(65546), i16::MAX);
+        assert_eq!(gfx_coordinate(-40000), i16::MIN);
+        assert_eq!(gfx_coordinate(i32::MAX), i16::MAX);
+    }
+}
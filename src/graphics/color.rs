@@ -1,9 +1,11 @@
+use std::str::FromStr;
+
 use crate::game::common::ColorT;
 
 
 
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Color {
     pub r: ColorT,
     pub g: ColorT,
@@ -14,7 +16,7 @@ pub struct Color {
 #[allow(dead_code)]
 impl Color {
 
-    pub(crate) fn to_sdl_color(&self) -> sdl2::pixels::Color {
+    pub(crate) fn to_sdl_color(self) -> sdl2::pixels::Color {
         sdl2::pixels::Color {
             r: self.r,
             g: self.g,
@@ -57,4 +59,46 @@ impl Color {
         b: 255,
         a: 255,
     };
-}
\ No newline at end of file
+
+    pub const TRANSPARENT: Color = Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 0,
+    };
+}
+
+/*
+ * Color from a name ("red") or an hexadecimal code ("#ff8000",
+ * "#ff800080" with alpha)
+ */
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(color: &str) -> Result<Self, Self::Err> {
+        let bad = || format!("Bad color {}", color);
+
+        if let Some(hex) = color.strip_prefix('#') {
+            if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+                return Err(bad());
+            }
+            let byte = |i: usize| ColorT::from_str_radix(&hex[i..i + 2], 16).map_err(|_| bad());
+            return Ok(Color {
+                r: byte(0)?,
+                g: byte(2)?,
+                b: byte(4)?,
+                a: if hex.len() == 8 { byte(6)? } else { 255 },
+            });
+        }
+
+        match color.to_lowercase().as_str() {
+            "red"         => Ok(Color::RED),
+            "green"       => Ok(Color::GREEN),
+            "blue"        => Ok(Color::BLUE),
+            "black"       => Ok(Color::BLACK),
+            "white"       => Ok(Color::WHITE),
+            "transparent" => Ok(Color::TRANSPARENT),
+            _             => Err(bad()),
+        }
+    }
+}
@@ -1,82 +1,614 @@
 
 use std::collections::HashMap;
 use sdl2::render::Texture;
+use crate::game::common::Size;
+use crate::error::{Game2dError, Game2dResult};
+use super::canvas::TexturesCreator;
+use super::bitmap_font::BitmapFont;
 use super::color::Color;
+use super::layout::{self, TextBlock, TextLayout};
 
 pub type FontContext<'a> = sdl2::ttf::Sdl2TtfContext;
 pub type Font<'ttf, 'rwops> = sdl2::ttf::Font<'ttf, 'rwops>;
 pub type FontStyle = sdl2::ttf::FontStyle;
 
+// Texts kept rendered by the fonts manager
+pub const FONTS_TEXT_CACHE_CAPACITY: usize = 256;
+
 #[derive(Hash, PartialEq, Eq, Debug)]
 struct FontKey {
     filename: String,
     point_size: u16,
 }
-#[derive(Hash, PartialEq, Eq, Debug)]
+/*
+ * FontKind : how the glyphs of a font are drawn
+ */
+#[derive(Hash, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum FontKind {
+    // TrueType font rendered by SDL_ttf
+    Ttf,
+    // Glyphs in images (BMFont, grid), see BitmapFont
+    Bitmap,
+}
+
+#[derive(Hash, PartialEq, Eq, Debug, Clone)]
 pub struct FontDetail {
   filename: String,
   point_size: u16,
   style: FontStyle,
+  kind: FontKind,
+}
+
+#[allow(dead_code)]
+impl FontDetail {
+    /*
+     * with_style()
+     *
+     * @Brief : Same font with another style (bold, italic...)
+     */
+    pub fn with_style(&self, style: FontStyle) -> FontDetail {
+      FontDetail { style, ..self.clone() }
+    }
+
+    pub fn get_style(&self) -> FontStyle {
+      self.style
+    }
+
+    pub fn get_kind(&self) -> FontKind {
+      self.kind
+    }
+}
+
+/*
+ * TextKey : a text rendered with a font, a style and a color
+ */
+#[derive(Hash, PartialEq, Eq, Debug, Clone)]
+struct TextKey {
+    font: FontDetail,
+    color: (u8, u8, u8, u8),
+    text: String,
+}
+
+impl TextKey {
+    fn new(font_detail: &FontDetail, text: &str, color: &Color) -> TextKey {
+      TextKey {
+        font: font_detail.clone(),
+        color: (color.r, color.g, color.b, color.a),
+        text: text.to_string(),
+      }
+    }
+}
+
+struct CachedText<V> {
+    value: V,
+    last_used: u64,
+}
+
+/*
+ * TextCacheStats : how well the texts cache works
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub capacity: usize,
+}
+
+/*
+ * TextCache : texts kept rendered, the least recently used are evicted
+ * first. The values evicted are given back to be destroyed.
+ */
+struct TextCache<V> {
+    entries: HashMap<TextKey, CachedText<V>>,
+    capacity: usize,
+    stats: TextCacheStats,
+    use_counter: u64,
+}
+
+impl<V> TextCache<V> {
+    fn new(capacity: usize) -> TextCache<V> {
+      TextCache {
+        entries: HashMap::new(),
+        capacity: capacity.max(1),
+        stats: TextCacheStats::default(),
+        use_counter: 0,
+      }
+    }
+
+    /*
+     * touch()
+     *
+     * @Brief : Use a text (false : not in the cache, insert it)
+     */
+    fn touch(&mut self, key: &TextKey) -> bool {
+      self.use_counter += 1;
+      match self.entries.get_mut(key) {
+        Some(cached) => {
+          cached.last_used = self.use_counter;
+          self.stats.hits += 1;
+          true
+        },
+        None => {
+          self.stats.misses += 1;
+          false
+        },
+      }
+    }
+
+    /*
+     * insert()
+     *
+     * @Brief : Add a text just used, return the texts evicted
+     */
+    fn insert(&mut self, key: TextKey, value: V) -> Vec<V> {
+      let mut evicted = Vec::new();
+      while self.entries.len() >= self.capacity {
+        evicted.extend(self.evict());
+      }
+      self.entries.insert(key, CachedText { value, last_used: self.use_counter });
+      evicted
+    }
+
+    fn get(&self, key: &TextKey) -> Option<&V> {
+      self.entries.get(key).map(|cached| &cached.value)
+    }
+
+    /*
+     * evict()
+     *
+     * @Brief : Remove the least recently used text
+     */
+    fn evict(&mut self) -> Option<V> {
+      let oldest = self.entries
+          .iter()
+          .min_by_key(|(_, cached)| cached.last_used)
+          .map(|(key, _)| key.clone())?;
+      self.stats.evictions += 1;
+      self.entries.remove(&oldest).map(|cached| cached.value)
+    }
+
+    /*
+     * set_capacity()
+     *
+     * @Brief : Number of texts kept (1 minimum), return the texts evicted
+     */
+    fn set_capacity(&mut self, capacity: usize) -> Vec<V> {
+      self.capacity = capacity.max(1);
+      let mut evicted = Vec::new();
+      while self.entries.len() > self.capacity {
+        evicted.extend(self.evict());
+      }
+      evicted
+    }
+
+    fn get_stats(&self) -> TextCacheStats {
+      TextCacheStats {
+        entries: self.entries.len(),
+        capacity: self.capacity,
+        ..self.stats
+      }
+    }
+
+    /*
+     * clear()
+     *
+     * @Brief : Remove all the texts (the stats are kept)
+     */
+    fn clear(&mut self) -> Vec<V> {
+      self.entries.drain().map(|(_, cached)| cached.value).collect()
+    }
+}
+
+/*
+ * destroy_textures()
+ *
+ * @Brief : Destroy textures no longer used
+ */
+fn destroy_textures(textures: Vec<Texture>) {
+    for texture in textures {
+      // SAFETY : textures aren't destroyed when dropped (unsafe_textures)
+      unsafe { texture.destroy() };
+    }
+}
+
+/*
+ * Text : a text kept rendered, rendered again only when it changes
+ *
+ *   let mut score = fonts_manager.new_text(&font, "0", Color::WHITE);
+ *   score.set_text(&format!("{}", points));
+ *   graphics.draw_text(&mut fonts_manager, &mut score, 10., 10.)?;
+ */
+#[derive(Debug)]
+pub struct Text {
+    id: u64,
+    font: FontDetail,
+    text: String,
+    color: Color,
+    changed: bool,
+    size: (Size, Size),
+}
+
+#[allow(dead_code)]
+impl Text {
+    pub fn set_text(&mut self, text: &str) -> &mut Self {
+      if self.text != text {
+        self.text = text.to_string();
+        self.changed = true;
+      }
+      self
+    }
+
+    pub fn get_text(&self) -> &str {
+      &self.text
+    }
+
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+      if self.color != color {
+        self.color = color;
+        self.changed = true;
+      }
+      self
+    }
+
+    pub fn get_color(&self) -> Color {
+      self.color
+    }
+
+    pub fn set_font(&mut self, font_detail: &FontDetail) -> &mut Self {
+      if &self.font != font_detail {
+        self.font = font_detail.clone();
+        self.changed = true;
+      }
+      self
+    }
+
+    /*
+     * get_size()
+     *
+     * @Brief : Size (width, height) of the text when last drawn
+     */
+    pub fn get_size(&self) -> (Size, Size) {
+      self.size
+    }
+
+    pub fn get_font(&self) -> &FontDetail {
+      &self.font
+    }
+
+    pub(crate) fn set_size(&mut self, size: (Size, Size)) {
+      self.size = size;
+    }
 }
 
 pub struct FontsManager<'ttf, 'rwops> {
-    texture_creator: sdl2::render::TextureCreator<sdl2::video::WindowContext>,
+    texture_creator: TexturesCreator,
     fonts: HashMap<FontKey, Font<'ttf, 'rwops>>,
+
+    // Texts rendered (least recently used are destroyed first)
+    texts_cache: TextCache<Texture>,
+
+    // Text objects
+    texts: HashMap<u64, Texture>,
+    next_text_id: u64,
+
+    // Bitmap fonts (by filename)
+    bitmap_fonts: HashMap<String, BitmapFont>,
 }
 
 impl<'ttf, 'rwops> FontsManager<'ttf, 'rwops> {
-    pub fn new(texture_creator: sdl2::render::TextureCreator<sdl2::video::WindowContext>) -> Self {
+    pub fn new(texture_creator: TexturesCreator) -> Self {
 
         Self {
-          texture_creator, fonts: HashMap::new()
+          texture_creator,
+          fonts: HashMap::new(),
+          texts_cache: TextCache::new(FONTS_TEXT_CACHE_CAPACITY),
+          texts: HashMap::new(),
+          next_text_id: 0,
+          bitmap_fonts: HashMap::new(),
         }
     }
 
-    pub fn load_font(&mut self, font_context: &'ttf mut FontContext, filename: String, point_size: u16) -> Result<FontDetail, String>{
+    pub fn load_font(&mut self, font_context: &'ttf mut FontContext, filename: String, point_size: u16) -> Game2dResult<FontDetail>{
       let font_detail = FontDetail{
         filename: filename.to_string(),
         point_size,
         style: FontStyle::NORMAL,
+        kind: FontKind::Ttf,
       };
-      let font_result: Result<sdl2::ttf::Font<'ttf, 'rwops>, String> = font_context.load_font(filename.clone(), point_size);
-      match font_result {
-          Ok(font ) => {
-            let my_font = font;
-            self.fonts.insert(FontKey {
-              filename: filename.to_string(),
-              point_size,
-            }, my_font);
-            return Ok(font_detail);
-          },
-          Err(e) => { return Err(e); }
+      font_context
+        .load_font(filename.clone(), point_size)
+        .map(|font| {
+          self.fonts.insert(FontKey {
+            filename: filename.to_string(),
+            point_size,
+          }, font);
+          font_detail
+        })
+        .map_err(|e| Game2dError::from_asset(&filename, e))
+    }
+
+    /*
+     * get_text_texture()
+     *
+     * @Brief : Texture of a text, rendered only if not in the cache
+     */
+    pub(crate) fn get_text_texture(&mut self, font_detail: &FontDetail, text: &str, color: &Color) -> Game2dResult<&Texture> {
+      let key = TextKey::new(font_detail, text, color);
+
+      if !self.texts_cache.touch(&key) {
+        let texture = self.draw_font(font_detail, text.to_string(), color)?;
+        destroy_textures(self.texts_cache.insert(key.clone(), texture));
+      }
+      Ok(self.texts_cache.get(&key).expect("text just cached"))
+    }
+
+    /*
+     * set_text_cache_capacity()
+     *
+     * @Brief : Number of texts kept rendered (1 minimum)
+     */
+    pub fn set_text_cache_capacity(&mut self, capacity: usize) {
+      destroy_textures(self.texts_cache.set_capacity(capacity));
+    }
+
+    pub fn get_text_cache_stats(&self) -> TextCacheStats {
+      self.texts_cache.get_stats()
+    }
+
+    /*
+     * clear_text_cache()
+     *
+     * @Brief : Destroy all the texts of the cache (the stats are kept)
+     */
+    pub fn clear_text_cache(&mut self) {
+      destroy_textures(self.texts_cache.clear());
+    }
+
+    /*
+     * new_text()
+     *
+     * @Brief : Create a Text object (rendered when first drawn)
+     */
+    pub fn new_text(&mut self, font_detail: &FontDetail, text: &str, color: Color) -> Text {
+      self.next_text_id += 1;
+      Text {
+        id: self.next_text_id,
+        font: font_detail.clone(),
+        text: text.to_string(),
+        color,
+        changed: true,
+        size: (0, 0),
+      }
+    }
+
+    /*
+     * get_object_texture()
+     *
+     * @Brief : Texture of a Text object, rendered again if it changed
+     */
+    pub(crate) fn get_object_texture(&mut self, text: &mut Text) -> Game2dResult<&Texture> {
+      if text.changed || !self.texts.contains_key(&text.id) {
+        let texture = self.draw_font(&text.font, text.text.clone(), &text.color)?;
+        let query = texture.query();
+        text.size = (query.width, query.height);
+        text.changed = false;
+        if let Some(old) = self.texts.insert(text.id, texture) {
+          // SAFETY : textures aren't destroyed when dropped (unsafe_textures)
+          unsafe { old.destroy() };
+        }
+      }
+      Ok(&self.texts[&text.id])
+    }
+
+    /*
+     * free_text()
+     *
+     * @Brief : Destroy the texture of a Text object
+     */
+    pub fn free_text(&mut self, text: Text) {
+      if let Some(texture) = self.texts.remove(&text.id) {
+        // SAFETY : textures aren't destroyed when dropped (unsafe_textures)
+        unsafe { texture.destroy() };
+      }
+    }
+
+    /*
+     * measure_text()
+     *
+     * @Brief : Size (width, height) of a text (newlines included)
+     */
+    pub fn measure_text(&mut self, font_detail: &FontDetail, text: &str) -> Game2dResult<(Size, Size)> {
+      let block = self.layout_text(font_detail, text, &TextLayout::new())?;
+      Ok((block.width, block.height))
+    }
+
+    /*
+     * layout_text()
+     *
+     * @Brief : Cut a text in lines and place them
+     */
+    pub fn layout_text(&mut self, font_detail: &FontDetail, text: &str, text_layout: &TextLayout) -> Game2dResult<TextBlock> {
+      if let Some(font) = self.get_bitmap_font(font_detail) {
+        let line_height = font.get_line_height();
+        return layout::layout_text(text, text_layout, line_height, line_height, |part| Ok(font.measure(part)));
+      }
+
+      let font = self.get_font(font_detail)?;
+      let line_height = font.height().max(0) as Size;
+      let line_skip = font.recommended_line_spacing().max(0) as Size;
+
+      layout::layout_text(text, text_layout, line_height, line_skip, |part| {
+        if part.is_empty() {
+          return Ok(0);
+        }
+        font.size_of(part)
+            .map(|(width, _)| width)
+            .map_err(|e| Game2dError::Font(e.to_string()))
+      })
+    }
+
+    /*
+     * get_line_skip()
+     *
+     * @Brief : Space between 2 lines of a font
+     */
+    pub fn get_line_skip(&mut self, font_detail: &FontDetail) -> Game2dResult<Size> {
+      if let Some(font) = self.get_bitmap_font(font_detail) {
+        return Ok(font.get_line_height());
+      }
+      Ok(self.get_font(font_detail)?.recommended_line_spacing().max(0) as Size)
+    }
+
+    /*
+     * add_bitmap_font()
+     *
+     * @Brief : Keep a bitmap font (its pages must be loaded in the
+     *          images manager of Graphics, see Graphics::load_bitmap_font)
+     */
+    pub(crate) fn add_bitmap_font(&mut self, filename: &str, font: BitmapFont) -> FontDetail {
+      let font_detail = FontDetail {
+        filename: filename.to_string(),
+        point_size: font.get_size(),
+        style: FontStyle::NORMAL,
+        kind: FontKind::Bitmap,
       };
+      self.bitmap_fonts.insert(filename.to_string(), font);
+      font_detail
     }
 
-    pub(crate) fn draw_font(&mut self, font_detail: &FontDetail, texte: String, color: &Color) -> Option<Texture> {
+    /*
+     * get_bitmap_font()
+     *
+     * @Brief : Bitmap font of a font detail (None : TTF font)
+     */
+    pub fn get_bitmap_font(&self, font_detail: &FontDetail) -> Option<&BitmapFont> {
+      match font_detail.kind {
+        FontKind::Bitmap => self.bitmap_fonts.get(&font_detail.filename),
+        FontKind::Ttf => Option::None,
+      }
+    }
 
+    /*
+     * get_font()
+     *
+     * @Brief : Loaded font with the style of the font detail
+     */
+    fn get_font(&mut self, font_detail: &FontDetail) -> Game2dResult<&mut Font<'ttf, 'rwops>> {
       let font_key = FontKey{
         filename: font_detail.filename.clone(),
         point_size: font_detail.point_size,
       };
 
-      if self.fonts.contains_key(&font_key) {
-        let font = self.fonts.get_mut(&font_key).unwrap();
-        font.set_style(font_detail.style);
+      if font_detail.kind == FontKind::Bitmap {
+        return Err(Game2dError::Font(format!("Font {} is a bitmap font", font_key.filename)));
+      }
 
-        if let Ok(surface) = font
-            .render(texte.as_str())
-            .blended(Color::to_sdl_color(color)) {
+      let font = self.fonts
+          .get_mut(&font_key)
+          .ok_or_else(|| Game2dError::Font(format!("Font {} ({}) not loaded", font_key.filename, font_key.point_size)))?;
+      font.set_style(font_detail.style);
+      Ok(font)
+    }
+
+    pub(crate) fn draw_font(&mut self, font_detail: &FontDetail, texte: String, color: &Color) -> Game2dResult<Texture> {
+
+      let font = self.get_font(font_detail)?;
+
+      let surface = font
+          .render(texte.as_str())
+          .blended(color.to_sdl_color())
+          .map_err(|e| Game2dError::Font(e.to_string()))?;
 
-          if let Ok(texture) = self
-              .texture_creator
-              .create_texture_from_surface(&surface) {
-            return Some(texture);
-          }
-        }  
+      self.texture_creator.create_texture_from_surface(&surface)
+    }
 
+}
+impl<'ttf, 'rwops> Drop for FontsManager<'ttf, 'rwops> {
+    /*
+     * drop()
+     *
+     * @Brief : Destroy the textures of the texts (the renderer is kept
+     *          alive by the texture creator until the end of drop)
+     */
+    fn drop(&mut self) {
+      self.clear_text_cache();
+      destroy_textures(self.texts.drain().map(|(_, texture)| texture).collect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(text: &str) -> TextKey {
+      let font = FontDetail {
+        filename: String::from("fonts/Vera.ttf"),
+        point_size: 12,
+        style: FontStyle::NORMAL,
+        kind: FontKind::Ttf,
+      };
+      TextKey::new(&font, text, &Color::WHITE)
+    }
+
+    /*
+     * Use a text, inserted if not cached (value : its text)
+     */
+    fn use_text(cache: &mut TextCache<String>, text: &str) -> Vec<String> {
+      match cache.touch(&key(text)) {
+        true => Vec::new(),
+        false => cache.insert(key(text), text.to_string()),
+      }
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+      let mut cache = TextCache::new(2);
+      assert!(use_text(&mut cache, "a").is_empty());
+      assert!(use_text(&mut cache, "b").is_empty());
+      // "a" used again : "b" is the oldest
+      assert!(use_text(&mut cache, "a").is_empty());
+      assert_eq!(use_text(&mut cache, "c"), ["b"]);
+      assert_eq!(cache.get(&key("a")), Some(&String::from("a")));
+      assert_eq!(cache.get(&key("b")), Option::None);
+
+      // Smaller : "a" (used before "c") goes
+      assert_eq!(cache.set_capacity(1), ["a"]);
+      assert_eq!(cache.get(&key("c")), Some(&String::from("c")));
+
+      // 1 minimum
+      assert!(cache.set_capacity(0).is_empty());
+      assert_eq!(use_text(&mut cache, "d"), ["c"]);
+    }
+
+    #[test]
+    fn cache_stats() {
+      let mut cache = TextCache::new(2);
+      for text in ["a", "b", "a", "a", "c", "b"] {
+        use_text(&mut cache, text);
       }
+      // Misses : a, b, c, b (evicted by c) - evictions : b, a
+      assert_eq!(cache.get_stats(), TextCacheStats { hits: 2, misses: 4, evictions: 2, entries: 2, capacity: 2 });
 
-      return Option::None;
+      let mut cleared = cache.clear();
+      cleared.sort();
+      assert_eq!(cleared, ["b", "c"]);
+      assert_eq!(cache.get_stats(), TextCacheStats { hits: 2, misses: 4, evictions: 2, entries: 0, capacity: 2 });
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn cache_keys_by_font_and_color() {
+      let mut cache = TextCache::new(4);
+      cache.touch(&key("a"));
+      cache.insert(key("a"), 1);
+
+      let mut red = key("a");
+      red.color = (255, 0, 0, 255);
+      assert!(!cache.touch(&red));
+      let mut bold = key("a");
+      bold.font.style = FontStyle::BOLD;
+      assert!(!cache.touch(&bold));
+      assert!(cache.touch(&key("a")));
+    }
+}
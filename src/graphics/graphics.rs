@@ -14,16 +14,31 @@
 
  */
 
+use crate::error::{Game2dError, Game2dResult};
 use crate::game::common::*;
 
-use super::fonts::{FontsManager, FontDetail, FontContext};
-use super::images::{ImagesManager, Quad, Image, _Image, ImageInformations, ImageFromString};
+use super::canvas::{SdlCanvas, TexturesCreator};
+use super::renderer::{Renderer, TextureCopy};
+use super::bitmap_font::BitmapFont;
+use super::fonts::{FontsManager, FontDetail, FontContext, Text};
+use super::layout::TextLayout;
+use super::rich_text::RichText;
+use super::images::{ImagesManager, Quad, Image, ImageInformations, ImageFromString, RenderTarget, render_target_key};
 use super::color::Color;
-use sdl2::render::{Canvas, BlendMode, TextureCreator};
-use sdl2::video::{Window, WindowContext};
-use sdl2::EventPump;
+use super::camera::Camera2D;
+use super::view::{LogicalView, ScalePolicy};
+use super::window::{FullscreenMode, WindowSettings};
+use sdl2::image::LoadSurface;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::Texture;
+use sdl2::surface::Surface;
+use sdl2::{EventPump, GameControllerSubsystem};
 
-pub type FontsCreator = TextureCreator<WindowContext>;
+pub type FontsCreator = TexturesCreator;
+
+// Segments of a full circle (depends on the radius on the screen)
+pub const GRAPHICS_MIN_SEGMENTS: usize = 12;
+pub const GRAPHICS_MAX_SEGMENTS: usize = 256;
 
 #[allow(dead_code)]
 pub struct Rectangle {
@@ -44,19 +59,59 @@ pub trait Drawable {
 }
 
 #[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum DrawMode {
     Fill,
     Line,
 }
 
+/*
+ * LineStyle : how lines and edges are drawn
+ */
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineStyle {
+    // Pixelated (fastest)
+    Rough,
+    // Anti-aliased
+    Smooth,
+}
+
+/*
+ * TextPlacement : where and how a line of text is drawn
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct TextPlacement {
+    x: Position,
+    y: Position,
+    angle: Angle,
+    sx: Transformation,
+    sy: Transformation,
+    ox: Position,
+    oy: Position,
+}
+
+impl TextPlacement {
+    /*
+     * at()
+     *
+     * @Brief : Text at a position, without rotation nor scale
+     */
+    fn at(x: Position, y: Position) -> TextPlacement {
+        TextPlacement { x, y, angle: 0., sx: 1., sy: 1., ox: 0., oy: 0. }
+    }
+}
+
 pub struct Graphics {
 
     //===== SDL2
-    sdl_canvas: Canvas<Window>,
-    pub(crate) sdl_event_pump: EventPump,
+    renderer: Box<dyn Renderer>,
+    pub(crate) sdl_event_pump: Option<EventPump>,
+    pub(crate) sdl_game_controller: Option<GameControllerSubsystem>,
 
     //==== Images
     images_manager: ImagesManager,
+    target: Option<String>,
 
     //==== Color
     actual_color: Color,
@@ -67,8 +122,26 @@ pub struct Graphics {
     //==== Fonts
     actual_font: Option<FontDetail>,
 
+    //==== Lines
+    line_width: Transformation,
+    line_style: LineStyle,
+
     //==== Scale
     actuel_scale: Scale2d,
+
+    //==== Logical resolution
+    logical_size: Option<(Size, Size, ScalePolicy)>,
+    view: Option<LogicalView>,
+
+    //==== Camera (world to screen)
+    camera: Option<Transform2d>,
+
+    //==== Transform (push / pop)
+    transform: Transform2d,
+    transform_stack: Vec<Transform2d>,
+
+    //==== Window
+    vsync: bool,
 }
 
 #[allow(dead_code)]
@@ -89,45 +162,121 @@ impl Graphics {
         width: Size,
         height: Size,
         fullscreen: bool,
-    ) -> Option<Graphics> {
+    ) -> Game2dResult<Graphics> {
 
-        let sdl_context = sdl2::init().unwrap();
-        let video_subsystem = sdl_context.video().unwrap();
+        let mut settings = WindowSettings::new(title, width, height);
+        if fullscreen {
+            settings.set_fullscreen(FullscreenMode::Exclusive);
+        }
+        Graphics::from_settings(&settings)
+    }
+
+    /***********************************************************
+     * from_settings()
+     *
+     * @brief : Create an instance of Graphics with a window
+     *          (fullscreen mode, vsync, icon...)
+     *
+     * @return : Instance of Graphics
+     **********************************************************/
+    pub fn from_settings(settings: &WindowSettings) -> Game2dResult<Graphics> {
+
+        let sdl_context = sdl2::init().map_err(Game2dError::Init)?;
+        let video_subsystem = sdl_context.video().map_err(Game2dError::Init)?;
 
 
         /* Create the window */
-        let mut window = video_subsystem
-            .window(title, width, height)
-            .position_centered()
+        let mut window_builder = video_subsystem.window(&settings.title, settings.width, settings.height);
+        window_builder.position_centered();
+        if settings.resizable {
+            window_builder.resizable();
+        }
+        let mut window = window_builder
             .build()
-            .unwrap();
+            .map_err(|e| Game2dError::Init(e.to_string()))?;
 
         // Full screen ?
-        if fullscreen {
-            window
-                .set_fullscreen(sdl2::video::FullscreenType::True)
-                .unwrap();
+        window
+            .set_fullscreen(settings.fullscreen.to_sdl_fullscreen())
+            .map_err(Game2dError::Init)?;
+
+        // Icon
+        if let Some(icon) = &settings.icon {
+            let surface = Surface::from_file(icon).map_err(|e| Game2dError::from_asset(icon, e))?;
+            window.set_icon(surface);
         }
 
         // Textures / Primitives ...
-        let mut canvas = window.into_canvas().build().unwrap();
-        canvas.set_blend_mode(BlendMode::Blend); // Accept Alpha
-
-        let images_manager = ImagesManager::new(canvas.texture_creator());
+        let mut canvas_builder = window.into_canvas();
+        if settings.vsync {
+            canvas_builder = canvas_builder.present_vsync();
+        }
+        let canvas = canvas_builder
+            .build()
+            .map_err(|e| Game2dError::Init(e.to_string()))?;
 
         // Events
-        let event_pump = sdl_context.event_pump().unwrap();
+        let event_pump = sdl_context.event_pump().map_err(Game2dError::Init)?;
+
+        // Gamepads (optional, the game runs without them)
+        let game_controller = sdl_context.game_controller().ok();
+
+        let mut graphics = Graphics::from_renderer(Box::new(SdlCanvas::from_window(canvas)), Some(event_pump));
+        graphics.sdl_game_controller = game_controller;
+        graphics.vsync = settings.vsync;
+        Ok(graphics)
+    }
+
+    /***********************************************************
+     * new_headless()
+     *
+     * @brief : Create an instance of Graphics without window,
+     *          rendering in an in-memory surface (tests, CI)
+     *
+     * @return : Instance of Graphics
+     **********************************************************/
+    pub fn new_headless(
+        width: Size,
+        height: Size,
+    ) -> Game2dResult<Graphics> {
+
+        let canvas = SdlCanvas::new_headless(width, height)?;
+        Ok(Graphics::from_renderer(Box::new(canvas), Option::None))
+    }
+
+    /***********************************************************
+     * with_renderer()
+     *
+     * @brief : Create an instance of Graphics drawing with a
+     *          renderer (recording, software...), without events
+     *
+     * @return : Instance of Graphics
+     **********************************************************/
+    pub fn with_renderer(renderer: Box<dyn Renderer>) -> Graphics {
+        Graphics::from_renderer(renderer, Option::None)
+    }
+
+    /***********************************************************
+     * from_renderer()
+     *
+     * @brief : Create Graphics on a renderer
+     **********************************************************/
+    fn from_renderer(mut renderer: Box<dyn Renderer>, event_pump: Option<EventPump>) -> Graphics {
+
+        let images_manager = ImagesManager::new(renderer.textures_creator());
 
         // Clear screen and show
-        canvas.clear();
-        canvas.present();
+        renderer.clear(Color::BLACK);
+        renderer.present();
 
         // Create Graphics
-        Some(Graphics {
-            sdl_canvas: canvas,
+        Graphics {
+            renderer,
             images_manager,
+            target: Option::None,
 
             sdl_event_pump: event_pump,
+            sdl_game_controller: Option::None,
 
             actual_color: Color::BLACK,
             default_color: Color::BLACK,
@@ -135,9 +284,168 @@ impl Graphics {
             font_color: Color::WHITE,
 
             actual_font: Option::None,
-            actuel_scale: Scale2d { sx: 1., sy: 1. }
-            
-        })
+
+            line_width: 1.,
+            line_style: LineStyle::Rough,
+            actuel_scale: Scale2d { sx: 1., sy: 1. },
+
+            logical_size: Option::None,
+            view: Option::None,
+
+            camera: Option::None,
+
+            transform: Transform2d::IDENTITY,
+            transform_stack: Vec::new(),
+
+            vsync: false,
+        }
+    }
+
+    /***********************************************************
+     * is_headless()
+     *
+     * @Brief : Graphics renders in memory (no window)
+     */
+    pub fn is_headless(&self) -> bool {
+        self.renderer.window().is_none()
+    }
+
+    //=======================================================================
+    //                               WINDOW
+    //=======================================================================
+
+    /***********************************************************
+     * set_fullscreen()
+     *
+     * @brief : Switch between windowed, borderless and exclusive
+     *          fullscreen
+     */
+    pub fn set_fullscreen(&mut self, fullscreen: FullscreenMode) -> Game2dResult<()> {
+        self.renderer
+            .window_mut()
+            .ok_or_else(|| Game2dError::Render(String::from("No window")))?
+            .set_fullscreen(fullscreen.to_sdl_fullscreen())
+            .map_err(Game2dError::Render)
+    }
+
+    /***********************************************************
+     * get_fullscreen()
+     *
+     * @brief : Fullscreen mode of the window (None : no window)
+     */
+    pub fn get_fullscreen(&self) -> Option<FullscreenMode> {
+        self.renderer
+            .window()
+            .map(|w| FullscreenMode::from_sdl_fullscreen(w.fullscreen_state()))
+    }
+
+    /***********************************************************
+     * toggle_fullscreen()
+     *
+     * @brief : Switch between windowed and a fullscreen mode
+     */
+    pub fn toggle_fullscreen(&mut self, fullscreen: FullscreenMode) -> Game2dResult<()> {
+        match self.get_fullscreen() {
+            Some(FullscreenMode::Windowed) => self.set_fullscreen(fullscreen),
+            _ => self.set_fullscreen(FullscreenMode::Windowed),
+        }
+    }
+
+    /***********************************************************
+     * is_vsync()
+     *
+     * @brief : Vsync chosen when the window was created (see
+     *          WindowSettings::set_vsync)
+     **********************************************************/
+    pub fn is_vsync(&self) -> bool {
+        self.vsync
+    }
+
+    /***********************************************************
+     * set_title()
+     *
+     * @brief : Set the title of the window
+     */
+    pub fn set_title(&mut self, title: &str) -> Game2dResult<()> {
+        self.renderer
+            .window_mut()
+            .ok_or_else(|| Game2dError::Render(String::from("No window")))?
+            .set_title(title)
+            .map_err(|e| Game2dError::Render(e.to_string()))
+    }
+
+    /***********************************************************
+     * set_icon()
+     *
+     * @brief : Set the icon of the window from an image file
+     */
+    pub fn set_icon(&mut self, filename: &str) -> Game2dResult<()> {
+        let surface = Surface::from_file(filename).map_err(|e| Game2dError::from_asset(filename, e))?;
+        self.renderer
+            .window_mut()
+            .ok_or_else(|| Game2dError::Render(String::from("No window")))?
+            .set_icon(surface);
+        Ok(())
+    }
+
+    /***********************************************************
+     * get_window_size()
+     *
+     * @brief : Size of the window (None : no window)
+     */
+    pub fn get_window_size(&self) -> Option<(Size, Size)> {
+        self.renderer.window().map(|w| w.size())
+    }
+
+    /***********************************************************
+     * set_text_input()
+     *
+     * @Brief : Start / stop the SDL text input of the window
+     *          (IME, on-screen keyboard)
+     */
+    pub(crate) fn set_text_input(&mut self, active: bool) {
+        if let Some(window) = self.renderer.window() {
+            let text_input = window.subsystem().text_input();
+            if active {
+                text_input.start();
+            } else {
+                text_input.stop();
+            }
+        }
+    }
+
+    /***********************************************************
+     * get_output_size()
+     *
+     * @Brief : Size (width, height) of the render output
+     */
+    pub fn get_output_size(&self) -> Option<(Size, Size)> {
+        self.renderer.output_size().ok()
+    }
+
+    /***********************************************************
+     * read_pixels()
+     *
+     * @Brief : Read back the pixels rendered (RGBA, 4 bytes per pixel,
+     *          row after row)
+     */
+    pub fn read_pixels(&mut self) -> Game2dResult<Vec<u8>> {
+        self.renderer.read_pixels()
+    }
+
+    /***********************************************************
+     * get_pixel()
+     *
+     * @Brief : Read back the color of one pixel
+     */
+    pub fn get_pixel(&mut self, x: Size, y: Size) -> Option<Color> {
+        let (width, height) = self.get_output_size()?;
+        if x >= width || y >= height {
+            return Option::None;
+        }
+        let pixels = self.read_pixels().ok()?;
+        let i = ((y * width + x) * 4) as usize;
+        Some(Color { r: pixels[i], g: pixels[i + 1], b: pixels[i + 2], a: pixels[i + 3] })
     }
 
     /***********************************************************
@@ -149,21 +457,235 @@ impl Graphics {
         self.actuel_scale = scale;
     }
 
+    /***********************************************************
+     * set_logical_size()
+     *
+     * @Brief : Draw in a fixed logical size scaled to the output
+     *          (None : draw in the output size)
+     */
+    pub fn set_logical_size(&mut self, logical_size: Option<(Size, Size)>, policy: ScalePolicy) {
+        self.logical_size = logical_size.map(|(w, h)| (w, h, policy));
+    }
+
+    /***********************************************************
+     * get_logical_size()
+     *
+     * @Brief : Logical size (None : no logical resolution)
+     */
+    pub fn get_logical_size(&self) -> Option<(Size, Size)> {
+        self.logical_size.map(|(w, h, _)| (w, h))
+    }
+
+    /***********************************************************
+     * get_logical_view()
+     *
+     * @Brief : Where the logical size is drawn on the output
+     */
+    pub fn get_logical_view(&self) -> Option<LogicalView> {
+        let (width, height, policy) = self.logical_size?;
+        let output = self.get_output_size()?;
+        Some(LogicalView::new(output, (width, height), policy))
+    }
+
+    /***********************************************************
+     * to_logical()
+     *
+     * @Brief : Output position (mouse...) to logical position
+     */
+    pub fn to_logical(&self, x: Position, y: Position) -> (Position, Position) {
+        match self.get_logical_view() {
+            Some(view) => view.to_logical(x, y),
+            None => (x, y),
+        }
+    }
+
+    /***********************************************************
+     * attach_camera()
+     *
+     * @Brief : Draw in world space, seen by the camera (until
+     *          detach_camera())
+     */
+    pub fn attach_camera(&mut self, camera: &Camera2D) {
+        self.camera = Some(camera.get_transform());
+    }
+
+    /***********************************************************
+     * detach_camera()
+     *
+     * @Brief : Draw in screen space (HUD...)
+     */
+    pub fn detach_camera(&mut self) {
+        self.camera = Option::None;
+    }
+
+    //=======================================================================
+    //                              TRANSFORM
+    //=======================================================================
+
+    /***********************************************************
+     * push()
+     *
+     * @Brief : Save the current transformation (restored by pop())
+     */
+    pub fn push(&mut self) {
+        self.transform_stack.push(self.transform);
+    }
+
+    /***********************************************************
+     * pop()
+     *
+     * @Brief : Restore the transformation saved by the last push()
+     */
+    pub fn pop(&mut self) -> Game2dResult<()> {
+        self.transform = self.transform_stack
+            .pop()
+            .ok_or_else(|| Game2dError::Render(String::from("pop() without push()")))?;
+        Ok(())
+    }
+
+    /***********************************************************
+     * translate()
+     *
+     * @Brief : Move the origin of the next drawings
+     */
+    pub fn translate(&mut self, dx: Position, dy: Position) {
+        self.apply_transform(&Transform2d::translation(dx, dy));
+    }
+
+    /***********************************************************
+     * rotate()
+     *
+     * @Brief : Rotate the next drawings around the origin (degrees)
+     */
+    pub fn rotate(&mut self, angle: Angle) {
+        self.apply_transform(&Transform2d::rotation(angle));
+    }
+
+    /***********************************************************
+     * scale()
+     *
+     * @Brief : Scale the next drawings from the origin
+     */
+    pub fn scale(&mut self, sx: Transformation, sy: Transformation) {
+        self.apply_transform(&Transform2d::scaling(sx, sy));
+    }
+
+    /***********************************************************
+     * apply_transform()
+     *
+     * @Brief : Apply a transformation to the next drawings
+     *          (in the current coordinates)
+     */
+    pub fn apply_transform(&mut self, transform: &Transform2d) {
+        self.transform = transform.then(&self.transform);
+    }
+
+    /***********************************************************
+     * origin()
+     *
+     * @Brief : Reset the current transformation (the stack is kept)
+     */
+    pub fn origin(&mut self) {
+        self.transform = Transform2d::IDENTITY;
+    }
+
+    /***********************************************************
+     * get_transform()
+     *
+     * @Brief : Transformation applied to the positions drawn
+     *          (current transformation, then camera)
+     */
+    pub fn get_transform(&self) -> Transform2d {
+        match &self.camera {
+            Some(camera) => self.transform.then(camera),
+            None => self.transform,
+        }
+    }
+
+    /***********************************************************
+     * transform_point()
+     *
+     * @Brief : Screen position of a position in the current
+     *          coordinates
+     */
+    pub fn transform_point(&self, x: Position, y: Position) -> (Position, Position) {
+        self.get_transform().apply(x, y)
+    }
+
+    /***********************************************************
+     * inverse_transform_point()
+     *
+     * @Brief : Position in the current coordinates of a screen
+     *          position (mouse picking)
+     */
+    pub fn inverse_transform_point(&self, x: Position, y: Position) -> (Position, Position) {
+        match self.get_transform().inverse() {
+            Some(inverse) => inverse.apply(x, y),
+            None => (x, y),
+        }
+    }
+
+    /***********************************************************
+     * transform_copy()
+     *
+     * @Brief : Place a texture copy with the transformation
+     *          (the rotation center keeps its place). A mirror
+     *          of the transformation flips the copy horizontally
+     *          (true returned)
+     */
+    fn transform_copy(&self, dst: Rect, center: Option<Point>, angle: Angle) -> (Rect, Option<Point>, Angle, bool) {
+        let transform = self.get_transform();
+        if transform == Transform2d::IDENTITY {
+            return (dst, center, angle, false);
+        }
+
+        let scale = transform.get_scale();
+        let mirrored = transform.is_mirrored();
+        let (cx, cy) = match center {
+            Some(c) => (c.x() as Position, c.y() as Position),
+            None => (dst.width() as Position / 2., dst.height() as Position / 2.),
+        };
+        let (px, py) = transform.apply(dst.x() as Position + cx, dst.y() as Position + cy);
+
+        // Flipped : the center is on the other side of the copy
+        let cx = match mirrored {
+            true => dst.width() as Position - cx,
+            false => cx,
+        };
+        let (sx, sy) = (scale.sx.abs(), scale.sy);
+        let (cx, cy) = (cx * sx, cy * sy);
+        let angle = match mirrored {
+            true => transform.get_rotation() - angle,
+            false => transform.get_rotation() + angle,
+        };
+        (
+            Rect::new(
+                (px - cx).round() as i32,
+                (py - cy).round() as i32,
+                (dst.width() as Transformation * sx).round() as u32,
+                (dst.height() as Transformation * sy).round() as u32,
+            ),
+            Some(Point::new(cx.round() as i32, cy.round() as i32)),
+            angle,
+            mirrored,
+        )
+    }
+
     /***********************************************************
      * get_fonts_creator
      *
      * @Brief : Create a texture for fonts
      */
     pub fn get_fonts_creator(&mut self) -> FontsCreator {
-        self.sdl_canvas.texture_creator() as FontsCreator
+        self.renderer.textures_creator()
     }
 
     /***********************************************************
      * create_fonts_context()
      * 
      */
-    pub fn create_fonts_context() -> FontContext<'static> {
-        sdl2::ttf::init().unwrap() as FontContext
+    pub fn create_fonts_context() -> Game2dResult<FontContext<'static>> {
+        sdl2::ttf::init().map_err(|e| Game2dError::Init(e.to_string()))
     }
 
     /***********************************************************
@@ -186,7 +708,6 @@ impl Graphics {
      */
     pub fn set_color(&mut self, p_color: Color) {
         self.actual_color = p_color;
-        self.sdl_canvas.set_draw_color(self.actual_color.to_sdl_color());
     }
 
     /***********************************************************
@@ -194,124 +715,372 @@ impl Graphics {
      *
      * @Brief : Set actual color with default color
      *
-     * @parm 1 : Color information
+     * @parm 1 : Color information
+     */
+    pub fn set_color_to_default(&mut self) {
+        self.actual_color = self.default_color;
+    }
+
+    /***********************************************************
+     * set_background_color()
+     *
+     * @Brief : Set actual background
+     *
+     * @parm 1 : Color information
+     */
+    pub fn set_background_color(&mut self, color: Color) {
+        self.background_color = color;
+    }
+
+    /***********************************************************
+     * set_font_color()
+     *
+     * @Brief : Set actual font color
+     *
+     * @parm 1 : Color information
+     */
+    pub fn set_font_color(&mut self, color: Color) {
+        self.font_color = color;
+    }
+
+    /***********************************************************
+     * set_font()
+     *
+     * @Brief : Set font
+     */
+    pub fn set_font(&mut self, font_detail: FontDetail) {
+        self.actual_font = Some(font_detail);
+    }
+
+    /***********************************************************
+     * set_line_width()
+     *
+     * @Brief : Width of the lines (DrawMode::Line)
+     */
+    pub fn set_line_width(&mut self, width: Transformation) {
+        self.line_width = width.max(1.);
+    }
+
+    pub fn get_line_width(&self) -> Transformation {
+        self.line_width
+    }
+
+    /***********************************************************
+     * set_line_style()
+     *
+     * @Brief : Anti-aliased (Smooth) or not (Rough) lines and edges
+     */
+    pub fn set_line_style(&mut self, line_style: LineStyle) {
+        self.line_style = line_style;
+    }
+
+    pub fn get_line_style(&self) -> LineStyle {
+        self.line_style
+    }
+
+    /***********************************************************
+     * begin_draw()
+     *
+     * @brief : Prepare to drawing, call before drawing
+     **********************************************************/
+    pub(crate) fn begin_draw(&mut self) {
+
+        // Draw in the output
+        let _ = self.reset_target();
+
+        // Logical resolution (the output may have been resized)
+        let view = self.get_logical_view();
+        if view != self.view {
+            self.view = view;
+            let result = match view {
+                Some(v) => self.renderer.set_view(Some(v.viewport), v.sx, v.sy),
+                None => self.renderer.set_view(Option::None, 1., 1.),
+            };
+            if result.is_err() {
+                self.view = Option::None;
+            }
+        }
+
+        // Transformations don't leak from a frame to the next one
+        self.transform = Transform2d::IDENTITY;
+        self.transform_stack.clear();
+
+        self.renderer.clear(self.background_color);
+        self.set_color_to_default();
+
+    }
+
+    /***********************************************************
+     * end_draw()
+     *
+     * @brief : Call after drawing
+     **********************************************************/
+    pub(crate) fn end_draw(&mut self) {
+        let _ = self.reset_target();
+        self.renderer.present();
+    }
+
+    //=======================================================================
+    //                             PRIMITIVES
+    //=======================================================================
+    /***********************************************************
+     * draw_line()
+     *
+     * @brief : Draw a line
+     */
+    pub fn line(&mut self, x1: Position, y1: Position, x2: Position, y2: Position, color: Option<Color>) -> Game2dResult<()> {
+
+        let color = color.unwrap_or(self.actual_color);
+        self.stroke(&[(x1, y1), (x2, y2)], false, color)
+    }
+
+    /***********************************************************
+     * rectangle()
+     *
+     * @brief : Draw a rectangle
+     */
+    pub fn rectangle(&mut self, mode: DrawMode, x: Position, y: Position, width: Size, height: Size, color: Option<Color>) -> Game2dResult<()> {
+
+        let color = color.unwrap_or(self.actual_color);
+        let (x2, y2) = (x + width as Position, y + height as Position);
+        let transform = self.get_transform();
+
+        // Rotated or styled lines : the rectangle is a polygon
+        if !transform.is_axis_aligned() || (mode == DrawMode::Line && self.is_line_styled()) {
+            return self.shape(mode, &[(x, y), (x2, y), (x2, y2), (x, y2)], color);
+        }
+
+        let (x1, y1) = transform.apply(x, y);
+        let (x2, y2) = transform.apply(x2, y2);
+        let (x1, x2) = (x1.min(x2), x1.max(x2));
+        let (y1, y2) = (y1.min(y2), y1.max(y2));
+        self.renderer
+            .rectangle(
+                mode,
+                sdl2::rect::Rect::new(x1.round() as i32, y1.round() as i32, (x2 - x1).round() as u32, (y2 - y1).round() as u32),
+                color,
+            )
+    }
+
+    /***********************************************************
+     * rounded_rectangle()
+     *
+     * @brief : Draw a rectangle with rounded corners
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn rounded_rectangle(&mut self, mode: DrawMode, x: Position, y: Position, width: Size, height: Size, radius: Position, color: Option<Color>) -> Game2dResult<()> {
+
+        let (w, h) = (width as Position, height as Position);
+        let radius = radius.min(w / 2.).min(h / 2.);
+        if radius <= 0. {
+            return self.rectangle(mode, x, y, width, height, color);
+        }
+
+        let color = color.unwrap_or(self.actual_color);
+        let segments = self.segments(radius) / 4;
+        let mut points = Vec::new();
+        for (cx, cy, angle) in [
+            (x + radius, y + radius, 180.),
+            (x + w - radius, y + radius, 270.),
+            (x + w - radius, y + h - radius, 0.),
+            (x + radius, y + h - radius, 90.),
+        ] {
+            points.extend(arc_points(cx, cy, radius, radius, angle, angle + 90., segments));
+        }
+        self.shape(mode, &points, color)
+    }
+
+    /***********************************************************
+     * circle()
+     *
+     * @brief : Draw a circle (x, y : center)
+     */
+    pub fn circle(&mut self, mode: DrawMode, x: Position, y: Position, radius: Position, color: Option<Color>) -> Game2dResult<()> {
+        self.ellipse(mode, x, y, radius, radius, color)
+    }
+
+    /***********************************************************
+     * ellipse()
+     *
+     * @brief : Draw an ellipse (x, y : center)
+     */
+    pub fn ellipse(&mut self, mode: DrawMode, x: Position, y: Position, radius_x: Position, radius_y: Position, color: Option<Color>) -> Game2dResult<()> {
+
+        let color = color.unwrap_or(self.actual_color);
+        let segments = self.segments(radius_x.max(radius_y));
+        let mut points = arc_points(x, y, radius_x, radius_y, 0., 360., segments);
+        points.pop();
+        self.shape(mode, &points, color)
+    }
+
+    /***********************************************************
+     * arc()
+     *
+     * @brief : Draw an arc of circle (angles in degrees, 0 : right,
+     *          90 : down). Fill : the part cut by the chord
      */
-    pub fn set_color_to_default(&mut self) {
-        self.actual_color = self.default_color;
-        self.sdl_canvas.set_draw_color(self.default_color.to_sdl_color());
+    #[allow(clippy::too_many_arguments)]
+    pub fn arc(&mut self, mode: DrawMode, x: Position, y: Position, radius: Position, angle1: Angle, angle2: Angle, color: Option<Color>) -> Game2dResult<()> {
+
+        let color = color.unwrap_or(self.actual_color);
+        let segments = self.arc_segments(radius, angle1, angle2);
+        let points = arc_points(x, y, radius, radius, angle1, angle2, segments);
+        match mode {
+            DrawMode::Line => self.stroke(&points, false, color),
+            DrawMode::Fill => self.shape(mode, &points, color),
+        }
     }
 
     /***********************************************************
-     * set_background_color()
-     *
-     * @Brief : Set actual background
+     * pie()
      *
-     * @parm 1 : Color information
+     * @brief : Draw a slice of circle (angles in degrees)
      */
-    pub fn set_background_color(&mut self, color: Color) {
-        self.background_color = color;
+    #[allow(clippy::too_many_arguments)]
+    pub fn pie(&mut self, mode: DrawMode, x: Position, y: Position, radius: Position, angle1: Angle, angle2: Angle, color: Option<Color>) -> Game2dResult<()> {
+
+        let color = color.unwrap_or(self.actual_color);
+        let segments = self.arc_segments(radius, angle1, angle2);
+        let mut points = vec![(x, y)];
+        points.extend(arc_points(x, y, radius, radius, angle1, angle2, segments));
+        self.shape(mode, &points, color)
     }
 
     /***********************************************************
-     * set_font_color()
-     *
-     * @Brief : Set actual font color
+     * triangle()
      *
-     * @parm 1 : Color information
+     * @brief : Draw a triangle
      */
-    pub fn set_font_color(&mut self, color: Color) {
-        self.font_color = color;
+    #[allow(clippy::too_many_arguments)]
+    pub fn triangle(&mut self, mode: DrawMode, x1: Position, y1: Position, x2: Position, y2: Position, x3: Position, y3: Position, color: Option<Color>) -> Game2dResult<()> {
+
+        let color = color.unwrap_or(self.actual_color);
+        self.shape(mode, &[(x1, y1), (x2, y2), (x3, y3)], color)
     }
 
     /***********************************************************
-     * set_font()
+     * polygon()
      *
-     * @Brief : Set font
+     * @brief : Draw a closed polygon
      */
-    pub fn set_font(&mut self, font_detail: FontDetail) {
-        self.actual_font = Some(font_detail);
+    pub fn polygon(&mut self, mode: DrawMode, points: &[Position2d], color: Option<Color>) -> Game2dResult<()> {
+
+        let color = color.unwrap_or(self.actual_color);
+        let points: Vec<(Position, Position)> = points.iter().map(|p| (p.x, p.y)).collect();
+        self.shape(mode, &points, color)
     }
 
     /***********************************************************
-     * begin_draw()
+     * polyline()
      *
-     * @brief : Prepare to drawing, call before drawing
-     **********************************************************/
-    pub(crate) fn begin_draw(&mut self) {
-
-        self.set_color(self.background_color);
-        self.sdl_canvas.clear();
-        self.set_color_to_default();
+     * @brief : Draw connected lines (not closed)
+     */
+    pub fn polyline(&mut self, points: &[Position2d], color: Option<Color>) -> Game2dResult<()> {
 
+        let color = color.unwrap_or(self.actual_color);
+        let points: Vec<(Position, Position)> = points.iter().map(|p| (p.x, p.y)).collect();
+        self.stroke(&points, false, color)
     }
 
     /***********************************************************
-     * end_draw()
+     * shape()
      *
-     * @brief : Call after drawing
-     **********************************************************/
-    pub(crate) fn end_draw(&mut self) {
-        self.sdl_canvas.present();
+     * @brief : Draw a closed shape (positions before transformation)
+     */
+    fn shape(&mut self, mode: DrawMode, points: &[(Position, Position)], color: Color) -> Game2dResult<()> {
+        match mode {
+            DrawMode::Line => self.stroke(points, true, color),
+            DrawMode::Fill => {
+                let points = self.to_screen_points(points);
+                if points.len() < 3 {
+                    return Ok(());
+                }
+                self.renderer.polygon(DrawMode::Fill, &points, color)?;
+                // Smooth : anti-aliased edges over the fill
+                if self.line_style == LineStyle::Smooth {
+                    self.renderer.aa_polygon(&points, color)?;
+                }
+                Ok(())
+            },
+        }
     }
 
-    //=======================================================================
-    //                             PRIMITIVES
-    //=======================================================================
     /***********************************************************
-     * draw_line()
+     * stroke()
      *
-     * @brief : Draw a line
+     * @brief : Draw the lines between positions (before
+     *          transformation) with the line width and style
      */
-    pub fn line(&mut self, x1: Position, y1: Position, x2: Position, y2: Position, color: Option<Color>) {
+    fn stroke(&mut self, points: &[(Position, Position)], closed: bool, color: Color) -> Game2dResult<()> {
+        let points = self.to_screen_points(points);
+        if points.len() < 2 {
+            return Ok(());
+        }
 
-        let actual_color = self.actual_color;
+        let scale = self.get_transform().get_scale();
+        let width = (self.line_width * (scale.sx.abs() + scale.sy) / 2.).round().clamp(1., u8::MAX as Transformation) as u8;
 
-        if let Some(color) = color {
-            self.set_color(color);
+        if width == 1 && closed {
+            return match self.line_style {
+                LineStyle::Rough => self.renderer.polygon(DrawMode::Line, &points, color),
+                LineStyle::Smooth => self.renderer.aa_polygon(&points, color),
+            };
         }
 
-        self.sdl_canvas
-            .draw_line(
-                sdl2::rect::Point::new(x1 as i32, y1 as i32),
-                sdl2::rect::Point::new(x2 as i32, y2 as i32),
-            )
-            .unwrap();
-        
-        if let Some(_) = color {
-            self.set_color(actual_color);
+        let mut segments: Vec<(Point, Point)> = points.windows(2).map(|p| (p[0], p[1])).collect();
+        if closed {
+            segments.push((points[points.len() - 1], points[0]));
+        }
+        for (start, end) in segments {
+            match (width, self.line_style) {
+                (1, LineStyle::Rough) => self.renderer.line(start, end, color)?,
+                (1, LineStyle::Smooth) => self.renderer.aa_line(start, end, color)?,
+                _ => self.renderer.thick_line(start, end, width, color)?,
+            }
         }
+        Ok(())
     }
 
     /***********************************************************
-     * rectangle()
+     * is_line_styled()
      *
-     * @brief : Draw a rectangle
+     * @brief : Lines need more than the basic SDL drawing
      */
-    pub fn rectangle(&mut self, mode: DrawMode, x: Position, y: Position, width: Size, height: Size, color: Option<Color>) {
-
-        let actual_color = self.actual_color;
+    fn is_line_styled(&self) -> bool {
+        self.line_width > 1. || self.line_style == LineStyle::Smooth
+    }
 
-        if let Some(color) = color {
-            self.set_color(color);
-        }
+    /***********************************************************
+     * to_screen_points()
+     *
+     * @brief : Transform positions to points on the screen
+     */
+    fn to_screen_points(&self, points: &[(Position, Position)]) -> Vec<Point> {
+        let transform = self.get_transform();
+        points
+            .iter()
+            .map(|(x, y)| {
+                let (x, y) = transform.apply(*x, *y);
+                Point::new(x.round() as i32, y.round() as i32)
+            })
+            .collect()
+    }
 
-        match mode {
-            DrawMode::Fill => {
-                self.sdl_canvas
-                    .fill_rect(sdl2::rect::Rect::new( x as i32, y as i32, width, height)) 
-                    .unwrap();
-        
-            },
-            DrawMode::Line => {
-                self.sdl_canvas
-                    .draw_rect(sdl2::rect::Rect::new( x as i32, y as i32, width, height)) 
-                    .unwrap();
-            },
-        }
+    /***********************************************************
+     * segments()
+     *
+     * @brief : Segments of a full circle of this radius (the
+     *          bigger on the screen, the smoother)
+     */
+    fn segments(&self, radius: Position) -> usize {
+        let scale = self.get_transform().get_scale();
+        let radius = radius.abs() * scale.sx.abs().max(scale.sy);
+        ((radius.sqrt() * 6.).ceil() as usize).clamp(GRAPHICS_MIN_SEGMENTS, GRAPHICS_MAX_SEGMENTS)
+    }
 
-        if let Some(_) = color {
-            self.set_color(actual_color);
-        }
+    fn arc_segments(&self, radius: Position, angle1: Angle, angle2: Angle) -> usize {
+        let sweep = ((angle2 - angle1).abs() / 360.).min(1.);
+        ((self.segments(radius) as Angle * sweep).ceil() as usize).max(2)
     }
 
     //=======================================================================
@@ -325,7 +1094,7 @@ impl Graphics {
     pub fn new_image(
         &mut self,
         filename: &str,
-    ) -> Result<Image, String> {
+    ) -> Game2dResult<Image> {
         self.images_manager.new_image(filename)
     }
 
@@ -334,24 +1103,85 @@ impl Graphics {
      *
      * @brief : Create a new quad from an image
      **********************************************************/
-    pub fn new_quad(&self, x: Position, y: Position, width: Size, height: Size, filename: String) -> Result<Quad, String> {
+    pub fn new_quad(&self, x: Position, y: Position, width: Size, height: Size, filename: String) -> Game2dResult<Quad> {
         
         // Image must be loaded in the images manager
         if let Some(image) = self.images_manager.get_image(&filename) {
             let image_w = image.get_width();
             let image_h = image.get_height();
             if x as Size + width > image_w || y as Size + height > image_h {
-                return Err(format!("Image {} must contain quad", filename));
+                Err(Game2dError::Image(format!("Image {} must contain quad", filename)))
             }
             else {
-                return Ok(Quad::new(filename, x, y, width, height))
+                Ok(Quad::new(filename, x, y, width, height))
             }
         }
         else {
-            return Err(format!("Image {} not loaded", filename));
+            Err(Game2dError::Image(format!("Image {} not loaded", filename)))
         }
+    }
 
-        
+    //=======================================================================
+    //                           RENDER TARGETS
+    //=======================================================================
+    /***********************************************************
+     * new_render_target()
+     *
+     * @brief : Create an image to draw in (drawn like any image)
+     **********************************************************/
+    pub fn new_render_target(&mut self, name: &str, width: Size, height: Size) -> Game2dResult<RenderTarget> {
+        // The texture may be replaced : don't keep drawing in it
+        if self.target.as_deref() == Some(name) {
+            self.reset_target()?;
+        }
+        self.images_manager.new_render_target(name, width, height)
+    }
+
+    /***********************************************************
+     * set_target()
+     *
+     * @brief : Draw in a render target until reset_target()
+     **********************************************************/
+    pub fn set_target(&mut self, render_target: &RenderTarget) -> Game2dResult<()> {
+        let name = render_target.get_name();
+        let image = self.images_manager
+            .get_image(&render_target.get_filename())
+            .ok_or_else(|| Game2dError::Image(format!("Render target {} not created", name)))?;
+        self.renderer.set_target(Some((name, &image.texture)))?;
+        self.target = Some(name.to_string());
+        Ok(())
+    }
+
+    /***********************************************************
+     * reset_target()
+     *
+     * @brief : Draw in the output again
+     **********************************************************/
+    pub fn reset_target(&mut self) -> Game2dResult<()> {
+        if self.target.is_some() {
+            self.renderer.set_target(Option::None)?;
+            self.target = Option::None;
+        }
+        Ok(())
+    }
+
+    /***********************************************************
+     * get_target()
+     *
+     * @brief : Name of the render target drawn in (None : output)
+     **********************************************************/
+    pub fn get_target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /***********************************************************
+     * clear()
+     *
+     * @brief : Clear the output or the render target (None :
+     *          transparent)
+     **********************************************************/
+    pub fn clear(&mut self, color: Option<Color>) {
+        self.renderer.clear(color.unwrap_or(Color::TRANSPARENT));
     }
 
     /***********************************************************
@@ -367,8 +1197,8 @@ impl Graphics {
         y: Position, 
         angle: Angle,
 
-    ) {
-        self.draw_full(drawable, x, y, angle, 1., 1., 0., 0.);
+    ) -> Game2dResult<()> {
+        self.draw_full(drawable, x, y, angle, 1., 1., 0., 0.)
     }
 
     /***********************************************************
@@ -377,6 +1207,7 @@ impl Graphics {
      * @brief : Draw image on screen (full (more options))
      *
      **********************************************************/
+    #[allow(clippy::too_many_arguments)]
     pub fn draw_full(
         &mut self,
         drawable: &dyn ImageFromString, 
@@ -388,7 +1219,10 @@ impl Graphics {
         ox: Position,
         oy: Position,
 
-    ) {
+    ) -> Game2dResult<()> {
+        if let Some(target) = self.target.as_deref().filter(|t| render_target_key(t) == drawable.get_filename()) {
+            return Err(Game2dError::Render(format!("Render target {} can't be drawn in itself", target)));
+        }
         let image = self.images_manager.get_image(drawable.get_filename().as_str());
 
         let mut scalex = sx * self.actuel_scale.sx;
@@ -396,24 +1230,6 @@ impl Graphics {
 
         match image {
             Some(i) => {
-                let mut dst = sdl2::rect::Rect::new((x * self.actuel_scale.sx)as i32,(y * self.actuel_scale.sy) as i32, i.get_width(), i.get_height());
-                dst.h = ((dst.h as Transformation) * scalex) as i32;
-                dst.w = ((dst.w as Transformation) * scaley) as i32;
-
-                let mut src: Option<sdl2::rect::Rect> = Option::None;
-
-                if let Some(q) = drawable.get_quad() {
-                    let rect = sdl2::rect::Rect::new((q.get_x() * self.actuel_scale.sx) as i32, (q.get_y() * self.actuel_scale.sy) as i32 , q.get_width(), q.get_height());
-                    src = Some(rect);
-                    dst.h = ((rect.h as Transformation) * scalex) as i32;
-                    dst.w = ((rect.w as Transformation) * scaley) as i32;
-                }
-
-                let mut w_center = Option::None;
-                if ox!=0. && oy!=0. {
-                    w_center = Some(sdl2::rect::Point::new(ox as i32, oy as i32));
-                }
-
                 let flip_h = 
                     if scalex < 0. {
                         scalex *= -1.;
@@ -432,56 +1248,55 @@ impl Graphics {
                         false 
                     };
 
-                self.sdl_canvas
-                    .copy_ex(
-                        &i.texture, 
-                        src, 
-                        dst, 
-                        angle, 
-                        w_center, 
-                        flip_h,
-                        flip_v, 
+                let mut dst = sdl2::rect::Rect::new((x * self.actuel_scale.sx)as i32,(y * self.actuel_scale.sy) as i32, i.get_width(), i.get_height());
+                dst.w = ((dst.w as Transformation) * scalex) as i32;
+                dst.h = ((dst.h as Transformation) * scaley) as i32;
+
+                let mut src: Option<sdl2::rect::Rect> = Option::None;
+
+                if let Some(q) = drawable.get_quad() {
+                    let rect = sdl2::rect::Rect::new(q.get_x() as i32, q.get_y() as i32, q.get_width(), q.get_height());
+                    src = Some(rect);
+                    dst.w = ((rect.w as Transformation) * scalex) as i32;
+                    dst.h = ((rect.h as Transformation) * scaley) as i32;
+                }
+
+                let mut w_center = Option::None;
+                if ox!=0. && oy!=0. {
+                    w_center = Some(sdl2::rect::Point::new(ox as i32, oy as i32));
+                }
+                let (dst, w_center, angle, mirrored) = self.transform_copy(dst, w_center, angle);
+
+                self.renderer
+                    .texture(
+                        i.get_filename().as_str(),
+                        &TextureCopy {
+                            texture: &i.texture,
+                            src,
+                            dst,
+                            angle,
+                            center: w_center,
+                            flip_horizontal: flip_h != mirrored,
+                            flip_vertical: flip_v,
+                        },
                     )
-                    .unwrap();
             }
-            None => {}
+            None => Err(Game2dError::Image(format!("Image {} not loaded", drawable.get_filename()))),
         }
     }
 
-    fn _draw_image(
+    fn _draw_text(
         &mut self,
-        _image: &_Image, 
-        x: Position, 
-        y: Position, 
-        angle: Angle,
-        sx: Transformation,
-        sy: Transformation,
-        ox: Position,
-        oy: Position,
-
-    ) {
+        text: &str,
+        color: Color,
+        texture: &Texture,
+        placement: TextPlacement,
+    ) -> Game2dResult<()> {
 
+        let TextPlacement { x, y, angle, sx, sy, ox, oy } = placement;
         let mut scalex = sx ;
         let mut scaley = sy ;
 
-        let mut dst = sdl2::rect::Rect::new(x as i32 , y  as i32, _image.get_width(), _image.get_height());
-        dst.h = ((dst.h as Transformation) * scalex) as i32;
-        dst.w = ((dst.w as Transformation) * scaley) as i32;
-
-        let mut src: Option<sdl2::rect::Rect> = Option::None;
-
-        if let Some(q) = _image.get_quad() {
-            let rect = sdl2::rect::Rect::new(q.get_x() as i32 , q.get_y() as i32 , q.get_width(), q.get_height());
-            src = Some(rect);
-            dst.h = ((rect.h as Transformation) * scalex) as i32;
-            dst.w = ((rect.w as Transformation) * scaley) as i32;
-        }
-
-        let mut w_center = Option::None;
-        if ox!=0. && oy!=0. {
-            w_center = Some(sdl2::rect::Point::new(ox as i32, oy as i32));
-        }
-
         let flip_h = 
                     if scalex < 0. {
                         scalex *= -1.;
@@ -500,20 +1315,31 @@ impl Graphics {
                 false 
             };
 
-        match  self.sdl_canvas
-            .copy_ex(
-                &_image.texture, 
-                src, 
-                dst, 
-                angle, 
-                w_center, 
-                flip_h,
-                flip_v, 
-            ) {
-                Ok(_) => {},
-                Err(e) => println!("{}", e),
-            }
+        let query = texture.query();
+        let mut dst = sdl2::rect::Rect::new(x as i32 , y  as i32, query.width, query.height);
+        dst.w = ((dst.w as Transformation) * scalex) as i32;
+        dst.h = ((dst.h as Transformation) * scaley) as i32;
 
+        let mut w_center = Option::None;
+        if ox!=0. && oy!=0. {
+            w_center = Some(sdl2::rect::Point::new(ox as i32, oy as i32));
+        }
+        let (dst, w_center, angle, mirrored) = self.transform_copy(dst, w_center, angle);
+
+        self.renderer
+            .text(
+                text,
+                color,
+                &TextureCopy {
+                    texture,
+                    src: Option::None,
+                    dst,
+                    angle,
+                    center: w_center,
+                    flip_horizontal: flip_h != mirrored,
+                    flip_vertical: flip_v,
+                },
+            )
     }
 
     //=======================================================================
@@ -544,7 +1370,7 @@ impl Graphics {
         x: Position, 
         y: Position, 
         color: Option<Color>,
-     ) {
+     ) -> Game2dResult<()> {
 
         self.print_full(fonts_manager, texte, x, y, color, 0., 1., 1., 0., 0.)
     }
@@ -554,6 +1380,7 @@ impl Graphics {
      *
      * @brief : Add a new font
      **********************************************************/
+     #[allow(clippy::too_many_arguments)]
      pub fn print_full(
         &mut self,
         fonts_manager: &mut FontsManager,
@@ -566,18 +1393,344 @@ impl Graphics {
         sy: Transformation,
         ox: Position,
         oy: Position,
-     ) {
+     ) -> Game2dResult<()> {
 
-        let mut local_color = self.font_color.clone();
+        let mut local_color = self.font_color;
         if let Some(color) = color {
-            local_color = color.clone();
+            local_color = color;
+        }
+
+        let font_detail = self.actual_font
+            .clone()
+            .ok_or_else(|| Game2dError::Font(String::from("No font set")))?;
+        self.draw_string(fonts_manager, &font_detail, &texte, local_color, TextPlacement { x, y, angle, sx, sy, ox, oy })
+    }
+
+    /***********************************************************
+     * printf()
+     *
+     * @brief : Print a text on several lines (newlines, wrapping,
+     *          alignment)
+     **********************************************************/
+    pub fn printf(
+        &mut self,
+        fonts_manager: &mut FontsManager,
+        texte: &str,
+        x: Position,
+        y: Position,
+        layout: &TextLayout,
+        color: Option<Color>,
+    ) -> Game2dResult<()> {
+
+        let color = color.unwrap_or(self.font_color);
+        let font_detail = self.actual_font
+            .clone()
+            .ok_or_else(|| Game2dError::Font(String::from("No font set")))?;
+
+        let block = fonts_manager.layout_text(&font_detail, texte, layout)?;
+        for piece in block.pieces.iter().filter(|p| !p.text.is_empty()) {
+            self.draw_string(fonts_manager, &font_detail, &piece.text, color, TextPlacement::at(x + piece.x, y + piece.y))?;
+        }
+        Ok(())
+    }
+
+    /***********************************************************
+     * print_rich()
+     *
+     * @brief : Print a text with markup (colors, styles, effects,
+     *          typewriter reveal), see RichText
+     **********************************************************/
+    pub fn print_rich(
+        &mut self,
+        fonts_manager: &mut FontsManager,
+        rich_text: &RichText,
+        x: Position,
+        y: Position,
+        color: Option<Color>,
+    ) -> Game2dResult<()> {
+
+        let color = color.unwrap_or(self.font_color);
+        let font_detail = self.actual_font
+            .clone()
+            .ok_or_else(|| Game2dError::Font(String::from("No font set")))?;
+        let line_skip = fonts_manager.get_line_skip(&font_detail)? as Position;
+
+        let visible = rich_text.get_visible_chars();
+        let mut shown = 0;
+        let (mut cx, mut cy) = (0., 0.);
+
+        for run in rich_text.get_runs() {
+            let run_font = font_detail.with_style(font_detail.get_style() | run.style);
+            let run_color = run.color.unwrap_or(color);
+
+            for (i, line) in run.text.split('\n').enumerate() {
+                if i > 0 {
+                    // The newline is a char of the reveal
+                    shown += 1;
+                    cx = 0.;
+                    cy += line_skip;
+                }
+                if shown >= visible {
+                    return Ok(());
+                }
+                if line.is_empty() {
+                    continue;
+                }
+
+                let length = line.chars().count();
+                let width = fonts_manager.measure_text(&run_font, line)?.0 as Position;
+
+                // Still text : the line at once
+                if !run.has_effects() && shown + length <= visible {
+                    self.draw_string(fonts_manager, &run_font, line, run_color, TextPlacement::at(x + cx, y + cy))?;
+                    shown += length;
+                    cx += width;
+                    continue;
+                }
+
+                // Glyph by glyph (advances added up)
+                let mut left: Position = 0.;
+                for (start, glyph) in line.char_indices() {
+                    if shown >= visible {
+                        return Ok(());
+                    }
+                    let glyph = &line[start..start + glyph.len_utf8()];
+                    if !glyph.trim().is_empty() {
+                        let (dx, dy) = rich_text.glyph_offset(run, shown);
+                        self.draw_string(fonts_manager, &run_font, glyph, run_color, TextPlacement::at(x + cx + left + dx, y + cy + dy))?;
+                    }
+                    left += fonts_manager.measure_text(&run_font, glyph)?.0 as Position;
+                    shown += 1;
+                }
+                cx += width;
+            }
+        }
+        Ok(())
+    }
+
+    /***********************************************************
+     * measure_text()
+     *
+     * @brief : Size (width, height) of a text with the current font
+     **********************************************************/
+    pub fn measure_text(&self, fonts_manager: &mut FontsManager, texte: &str) -> Game2dResult<(Size, Size)> {
+        let font_detail = self.actual_font
+            .as_ref()
+            .ok_or_else(|| Game2dError::Font(String::from("No font set")))?;
+        fonts_manager.measure_text(font_detail, texte)
+    }
+
+    /***********************************************************
+     * draw_text()
+     *
+     * @brief : Draw a Text object (rendered again only if it
+     *          changed)
+     **********************************************************/
+    pub fn draw_text(
+        &mut self,
+        fonts_manager: &mut FontsManager,
+        text: &mut Text,
+        x: Position,
+        y: Position,
+    ) -> Game2dResult<()> {
+        self.draw_text_full(fonts_manager, text, x, y, 0., 1., 1., 0., 0.)
+    }
+
+    /***********************************************************
+     * draw_text_full()
+     *
+     * @brief : Draw a Text object (full (more options))
+     **********************************************************/
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_text_full(
+        &mut self,
+        fonts_manager: &mut FontsManager,
+        text: &mut Text,
+        x: Position,
+        y: Position,
+        angle: Angle,
+        sx: Transformation,
+        sy: Transformation,
+        ox: Position,
+        oy: Position,
+    ) -> Game2dResult<()> {
+        let placement = TextPlacement { x, y, angle, sx, sy, ox, oy };
+
+        // Bitmap font : the glyphs are already in images
+        if let Some(font) = fonts_manager.get_bitmap_font(text.get_font()) {
+            text.set_size((font.measure(text.get_text()), font.get_line_height()));
+            return self._draw_bitmap_text(font, text.get_text(), text.get_color(), placement);
+        }
+
+        let texture = fonts_manager.get_object_texture(text)?;
+        self._draw_text(text.get_text(), text.get_color(), texture, placement)
+    }
+
+    /***********************************************************
+     * load_bitmap_font()
+     *
+     * @brief : Load an AngelCode BMFont (.fnt text or XML, pages
+     *          loaded as images)
+     **********************************************************/
+    pub fn load_bitmap_font(&mut self, fonts_manager: &mut FontsManager, filename: &str) -> Game2dResult<FontDetail> {
+        let content = std::fs::read_to_string(filename)
+            .map_err(|e| Game2dError::from_asset(filename, e.to_string()))?;
+        let font = BitmapFont::from_fnt(filename, &content)?;
+        for page in font.pages.iter() {
+            self.images_manager.new_image(page)?;
+        }
+        Ok(fonts_manager.add_bitmap_font(filename, font))
+    }
+
+    /***********************************************************
+     * load_grid_font()
+     *
+     * @brief : Load an image of glyphs of the same size (chars :
+     *          the glyphs in order, left to right, top to bottom)
+     **********************************************************/
+    pub fn load_grid_font(
+        &mut self,
+        fonts_manager: &mut FontsManager,
+        filename: &str,
+        glyph_width: Size,
+        glyph_height: Size,
+        chars: &str,
+    ) -> Game2dResult<FontDetail> {
+        let image = self.images_manager.new_image(filename)?;
+        let font = BitmapFont::from_grid(filename, image.get_width(), glyph_width, glyph_height, chars)?;
+        Ok(fonts_manager.add_bitmap_font(filename, font))
+    }
+
+    /***********************************************************
+     * draw_string()
+     *
+     * @brief : Draw a line of text with a font (TTF or bitmap)
+     **********************************************************/
+    fn draw_string(
+        &mut self,
+        fonts_manager: &mut FontsManager,
+        font_detail: &FontDetail,
+        text: &str,
+        color: Color,
+        placement: TextPlacement,
+    ) -> Game2dResult<()> {
+        if let Some(font) = fonts_manager.get_bitmap_font(font_detail) {
+            return self._draw_bitmap_text(font, text, color, placement);
+        }
+        let texture = fonts_manager.get_text_texture(font_detail, text, &color)?;
+        self._draw_text(text, color, texture, placement)
+    }
+
+    /***********************************************************
+     * _draw_bitmap_text()
+     *
+     * @brief : Draw a line of text glyph by glyph (the glyphs turn
+     *          around the origin of the text)
+     **********************************************************/
+    fn _draw_bitmap_text(
+        &mut self,
+        font: &BitmapFont,
+        text: &str,
+        color: Color,
+        placement: TextPlacement,
+    ) -> Game2dResult<()> {
+
+        let drawn = self.draw_bitmap_glyphs(font, text, color, placement);
+
+        // The pages can be drawn as images too (even after an error)
+        for page in font.pages.iter() {
+            if let Some(image) = self.images_manager.get_image_mut(page) {
+                image.texture.set_color_mod(255, 255, 255);
+                image.texture.set_alpha_mod(255);
+            }
         }
+        drawn
+    }
+
+    /***********************************************************
+     * draw_bitmap_glyphs()
+     *
+     * @brief : Draw the glyphs of a line of text, with the pages
+     *          tinted with the color of the text
+     **********************************************************/
+    fn draw_bitmap_glyphs(
+        &mut self,
+        font: &BitmapFont,
+        text: &str,
+        color: Color,
+        placement: TextPlacement,
+    ) -> Game2dResult<()> {
+
+        let TextPlacement { x, y, angle, sx, sy, ox, oy } = placement;
+        let (scalex, scaley) = (sx.abs(), sy.abs());
+        let mut pen: i32 = 0;
+        let mut previous = Option::None;
+
+        for c in text.chars() {
+            if let Some(p) = previous {
+                pen += font.get_kerning(p, c);
+            }
+            previous = Some(c);
+            let glyph = match font.get_glyph(c) {
+                Some(glyph) => *glyph,
+                None => continue,
+            };
 
-        if let Some(font_detail) = &mut self.actual_font {
-            if let Some(texture) = fonts_manager.draw_font(&font_detail, texte, &local_color) {
-                let image = _Image::from_texture(texture);
-                self._draw_image(&image, x, y, angle, sx, sy, ox, oy);
+            if glyph.width > 0 && glyph.height > 0 {
+                let page = &font.pages[glyph.page];
+                let (gx, gy) = ((pen + glyph.xoffset) as Position * scalex, glyph.yoffset as Position * scaley);
+                let dst = Rect::new(
+                    (x + gx) as i32,
+                    (y + gy) as i32,
+                    (glyph.width as Transformation * scalex).round() as u32,
+                    (glyph.height as Transformation * scaley).round() as u32,
+                );
+                let center = Some(Point::new((ox * scalex - gx) as i32, (oy * scaley - gy) as i32));
+                let (dst, center, angle, mirrored) = self.transform_copy(dst, center, angle);
+
+                // The page is tinted with the color of the text
+                let image = self.images_manager
+                    .get_image_mut(page)
+                    .ok_or_else(|| Game2dError::Image(format!("Image {} not loaded", page)))?;
+                image.texture.set_color_mod(color.r, color.g, color.b);
+                image.texture.set_alpha_mod(color.a);
+
+                let image = self.images_manager
+                    .get_image(page)
+                    .ok_or_else(|| Game2dError::Image(format!("Image {} not loaded", page)))?;
+                self.renderer
+                    .text(
+                        &c.to_string(),
+                        color,
+                        &TextureCopy {
+                            texture: &image.texture,
+                            src: Some(Rect::new(glyph.x, glyph.y, glyph.width, glyph.height)),
+                            dst,
+                            angle,
+                            center,
+                            flip_horizontal: mirrored,
+                            flip_vertical: false,
+                        },
+                    )?;
             }
+            pen += glyph.xadvance;
         }
+        Ok(())
     }
-}
\ No newline at end of file
+}
+
+/*
+ * arc_points()
+ *
+ * @Brief : Positions on an ellipse from angle1 to angle2 (degrees,
+ *          both included)
+ */
+fn arc_points(x: Position, y: Position, radius_x: Position, radius_y: Position, angle1: Angle, angle2: Angle, segments: usize) -> Vec<(Position, Position)> {
+    let segments = segments.max(1);
+    (0..=segments)
+        .map(|i| {
+            let angle = (angle1 + (angle2 - angle1) * i as Angle / segments as Angle).to_radians();
+            (x + radius_x * angle.cos() as Position, y + radius_y * angle.sin() as Position)
+        })
+        .collect()
+}
@@ -7,9 +7,11 @@
                                                            
                                                         
 *******************************************************************/
-use sdl2::image::LoadTexture;
 use std::collections::HashMap;
 
+use super::canvas::TexturesCreator;
+
+use crate::error::Game2dResult;
 use crate::game::common::{Size, Position};
 
 
@@ -63,19 +65,6 @@ impl From<sdl2::render::Texture> for _Image{
     }
 }
 
-impl _Image {
-    pub(crate) fn from_texture(texture: sdl2::render::Texture) -> Self {
-        let height = texture.query().height;
-        let width = texture.query().width;
-        Self {
-            texture,
-            height,
-            width,
-            filename: String::new(),
-        }
-    }
-}
-
 /*
  * Image 
  */
@@ -110,6 +99,55 @@ impl ImageInformations for Image {
     }
 }
 
+/*
+ * RenderTarget : Image to draw in (minimap, cached background...)
+ *
+ *   graphics.set_target(&minimap)?;
+ *   ... draw ...
+ *   graphics.reset_target()?;
+ *   graphics.draw(&minimap, x, y)?;
+ *
+ * The targets don't share their names with the images loaded
+ * (kept with RENDER_TARGET_PREFIX before their name)
+ */
+pub(crate) const RENDER_TARGET_PREFIX: &str = "render_target:";
+
+pub(crate) fn render_target_key(name: &str) -> String {
+    format!("{}{}", RENDER_TARGET_PREFIX, name)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderTarget {
+    name: String,
+    width: Size,
+    height: Size,
+}
+
+impl RenderTarget {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl ImageFromString for RenderTarget {}
+
+impl ImageInformations for RenderTarget {
+
+    fn get_filename(&self) -> String {
+        render_target_key(&self.name)
+    }
+    fn get_quad(&self) -> Option<Quad> {
+        Option::None
+    }
+    fn get_width(&self) -> Size {
+        self.width
+    }
+
+    fn get_height(&self) -> Size {
+        self.height
+    }
+}
+
 /*
  * Quad : A part of an image
  */
@@ -162,7 +200,7 @@ impl ImageInformations for Quad {
 //                            Images MANAGER
 //=======================================================================
 pub(in super) struct ImagesManager {
-    texture_creator: sdl2::render::TextureCreator<sdl2::video::WindowContext>,
+    texture_creator: TexturesCreator,
     images: HashMap<String, _Image>,
 }
 
@@ -173,7 +211,7 @@ impl ImagesManager {
      * 
      * @Brief : Create a new ImagesManager
      */
-    pub(crate) fn new(texture_creator: sdl2::render::TextureCreator<sdl2::video::WindowContext>) -> ImagesManager {
+    pub(crate) fn new(texture_creator: TexturesCreator) -> ImagesManager {
         ImagesManager {
             texture_creator,
             images: HashMap::new(),
@@ -188,21 +226,12 @@ impl ImagesManager {
     pub(crate) fn new_image(
         &mut self,
         filename: &str,
-    ) -> Result<Image, String> {
-        if let Some(image) = self.images.get(&filename.to_string()) {
+    ) -> Game2dResult<Image> {
+        if let Some(image) = self.images.get(filename) {
             return Ok(Image { filename: image.filename.to_string(), width: image.width, height: image.height });
         }
 
-        let texture_result = self.texture_creator.load_texture(filename);
-        let texture: sdl2::render::Texture;
-        match texture_result {
-            Err(e) => {
-                return Err(e);
-            }
-            Ok(t) => {
-                texture = t;
-            }
-        }
+        let texture = self.texture_creator.load_texture(filename)?;
 
         let height = texture.query().height;
         let width = texture.query().width;
@@ -220,12 +249,52 @@ impl ImagesManager {
     
     }
 
+    /*
+     * new_render_target()
+     * 
+     * @Brief : Create an image to draw in (a target with the same name
+     *          and size is kept, its content too)
+     */
+    pub(crate) fn new_render_target(
+        &mut self,
+        name: &str,
+        width: Size,
+        height: Size,
+    ) -> Game2dResult<RenderTarget> {
+        let render_target = RenderTarget { name: name.to_string(), width, height };
+        let key = render_target.get_filename();
+        if let Some(image) = self.images.get(&key) {
+            if image.width == width && image.height == height {
+                return Ok(render_target);
+            }
+        }
+
+        let texture = self.texture_creator.create_texture_target(width, height)?;
+        let replaced = self.images.insert(key.clone(), _Image {
+            filename: key,
+            width,
+            height,
+            texture,
+        });
+        if let Some(old) = replaced {
+            // SAFETY : textures aren't destroyed when dropped (unsafe_textures),
+            // the old one isn't the render target anymore
+            unsafe { old.texture.destroy() };
+        }
+
+        Ok(render_target)
+    }
+
     /*
      * get_image()
      * 
      * @Brief : Get a image from the image manager
      */
     pub fn get_image(&self, filename: &str) -> Option<&_Image> {
-        self.images.get(&filename.to_string())
+        self.images.get(filename)
+    }
+
+    pub(crate) fn get_image_mut(&mut self, filename: &str) -> Option<&mut _Image> {
+        self.images.get_mut(filename)
     }
 }
\ No newline at end of file
@@ -0,0 +1,377 @@
+/*================================================================
+ *                          L A Y O U T
+ *
+ * Text layout : lines (embedded newlines, word wrapping in a max
+ * width), alignment and line spacing. The texts are measured with
+ * the fonts of FontsManager :
+ *
+ *   let mut layout = TextLayout::new();
+ *   layout.set_max_width(Some(200)).set_align(TextAlign::Center);
+ *   let block = fonts_manager.layout_text(&font, "Hello world", &layout)?;
+ *   graphics.printf(&mut fonts_manager, "Hello world", x, y, &layout, None)?;
+ *================================================================*/
+use crate::error::Game2dResult;
+use crate::game::common::{Position, Size, Transformation};
+
+/*
+ * TextAlign : where the lines are in the width of the text
+ */
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+    // Words spread on all the max width (except the last line of a
+    // paragraph)
+    Justify,
+}
+
+/*
+ * TextLayout : how to lay a text out
+ */
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct TextLayout {
+    max_width: Option<Size>,
+    align: TextAlign,
+    line_spacing: Transformation,
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        TextLayout::new()
+    }
+}
+
+#[allow(dead_code)]
+impl TextLayout {
+    pub fn new() -> TextLayout {
+        TextLayout {
+            max_width: Option::None,
+            align: TextAlign::Left,
+            line_spacing: 1.,
+        }
+    }
+
+    /*
+     * set_max_width()
+     *
+     * @Brief : Wrap the words in a width (None : lines are only cut on
+     *          newlines)
+     */
+    pub fn set_max_width(&mut self, max_width: Option<Size>) -> &mut Self {
+        self.max_width = max_width;
+        self
+    }
+
+    pub fn get_max_width(&self) -> Option<Size> {
+        self.max_width
+    }
+
+    pub fn set_align(&mut self, align: TextAlign) -> &mut Self {
+        self.align = align;
+        self
+    }
+
+    pub fn get_align(&self) -> TextAlign {
+        self.align
+    }
+
+    /*
+     * set_line_spacing()
+     *
+     * @Brief : Space between lines (1 : line spacing of the font)
+     */
+    pub fn set_line_spacing(&mut self, line_spacing: Transformation) -> &mut Self {
+        self.line_spacing = line_spacing.max(0.);
+        self
+    }
+
+    pub fn get_line_spacing(&self) -> Transformation {
+        self.line_spacing
+    }
+}
+
+/*
+ * TextPiece : a part of the text at a position (relative to the
+ * top left corner of the block)
+ */
+#[derive(PartialEq, Debug, Clone)]
+pub struct TextPiece {
+    pub text: String,
+    pub x: Position,
+    pub y: Position,
+    pub width: Size,
+}
+
+/*
+ * TextBlock : a text laid out
+ */
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct TextBlock {
+    pub pieces: Vec<TextPiece>,
+    pub lines: usize,
+    pub width: Size,
+    pub height: Size,
+}
+
+/*
+ * Line : words of a line and if it ends a paragraph
+ */
+struct Line {
+    words: Vec<String>,
+    last: bool,
+}
+
+/*
+ * layout_text()
+ *
+ * @Brief : Lay a text out (measure : width of a text, line_height :
+ *          height of a line, line_skip : space between 2 lines)
+ */
+pub(crate) fn layout_text<F>(text: &str, layout: &TextLayout, line_height: Size, line_skip: Size, mut measure: F) -> Game2dResult<TextBlock>
+where
+    F: FnMut(&str) -> Game2dResult<Size>,
+{
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let paragraph = paragraph.trim_end_matches('\r');
+        let mut wrapped = match layout.max_width {
+            Some(max_width) => wrap(paragraph, max_width, &mut measure)?,
+            None => vec![Line { words: vec![paragraph.to_string()], last: false }],
+        };
+        if let Some(line) = wrapped.last_mut() {
+            line.last = true;
+        }
+        lines.extend(wrapped);
+    }
+
+    // Widths
+    let mut widths = Vec::with_capacity(lines.len());
+    for line in lines.iter() {
+        widths.push(measure(&line.words.join(" "))?);
+    }
+    let width = widths.iter().copied().max().unwrap_or(0);
+    let box_width = layout.max_width.unwrap_or(width) as Position;
+    let step = (line_skip as Transformation * layout.line_spacing).round();
+
+    let mut pieces = Vec::new();
+    for (i, (line, line_width)) in lines.iter().zip(widths.iter()).enumerate() {
+        let y = i as Position * step;
+        let justify = layout.align == TextAlign::Justify && layout.max_width.is_some() && !line.last && line.words.len() > 1;
+
+        if justify {
+            let mut word_widths = Vec::with_capacity(line.words.len());
+            for word in line.words.iter() {
+                word_widths.push(measure(word)?);
+            }
+            let words_width: Size = word_widths.iter().sum();
+            let gap = (box_width - words_width as Position) / (line.words.len() - 1) as Position;
+            let mut x: Position = 0.;
+            for (word, word_width) in line.words.iter().zip(word_widths) {
+                pieces.push(TextPiece { text: word.clone(), x: x.round(), y, width: word_width });
+                x += word_width as Position + gap;
+            }
+            continue;
+        }
+
+        let x = match layout.align {
+            TextAlign::Left | TextAlign::Justify => 0.,
+            TextAlign::Center => ((box_width - *line_width as Position) / 2.).round(),
+            TextAlign::Right => box_width - *line_width as Position,
+        };
+        pieces.push(TextPiece { text: line.words.join(" "), x, y, width: *line_width });
+    }
+
+    let height = match lines.len() {
+        0 => 0,
+        n => (n - 1) as Size * step as Size + line_height,
+    };
+    Ok(TextBlock {
+        pieces,
+        lines: lines.len(),
+        width: layout.max_width.unwrap_or(width).max(width),
+        height,
+    })
+}
+
+/*
+ * wrap()
+ *
+ * @Brief : Cut a paragraph in lines not wider than max_width (words
+ *          too wide are cut)
+ */
+fn wrap<F>(paragraph: &str, max_width: Size, measure: &mut F) -> Game2dResult<Vec<Line>>
+where
+    F: FnMut(&str) -> Game2dResult<Size>,
+{
+    let mut lines = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+
+    for word in paragraph.split_whitespace() {
+        let candidate = match words.is_empty() {
+            true => word.to_string(),
+            false => format!("{} {}", words.join(" "), word),
+        };
+        if measure(&candidate)? <= max_width {
+            words.push(word.to_string());
+            continue;
+        }
+
+        if !words.is_empty() {
+            lines.push(Line { words: std::mem::take(&mut words), last: false });
+        }
+
+        let mut rest = word;
+        while measure(rest)? > max_width {
+            let cut = longest_fit(rest, max_width, measure)?;
+            lines.push(Line { words: vec![rest[..cut].to_string()], last: false });
+            rest = &rest[cut..];
+        }
+        if !rest.is_empty() {
+            words.push(rest.to_string());
+        }
+    }
+
+    // Empty paragraph : an empty line
+    if !words.is_empty() || lines.is_empty() {
+        lines.push(Line { words, last: false });
+    }
+    Ok(lines)
+}
+
+/*
+ * longest_fit()
+ *
+ * @Brief : Bytes of the longest start of a word not wider than
+ *          max_width (1 char at least)
+ */
+fn longest_fit<F>(word: &str, max_width: Size, measure: &mut F) -> Game2dResult<usize>
+where
+    F: FnMut(&str) -> Game2dResult<Size>,
+{
+    let mut ends = word.char_indices().map(|(i, c)| i + c.len_utf8());
+    let mut fit = ends.next().unwrap_or(word.len());
+    for end in ends {
+        if measure(&word[..end])? > max_width {
+            break;
+        }
+        fit = end;
+    }
+    Ok(fit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 10 pixels per char, lines 12 high, 15 between lines
+    fn layout(text: &str, layout: &TextLayout) -> TextBlock {
+        layout_text(text, layout, 12, 15, |t| Ok(t.chars().count() as Size * 10)).unwrap()
+    }
+
+    fn texts(block: &TextBlock) -> Vec<&str> {
+        block.pieces.iter().map(|p| p.text.as_str()).collect()
+    }
+
+    #[test]
+    fn lines_on_newlines() {
+        let block = layout("ab\ncdef\n\ng", &TextLayout::new());
+        assert_eq!(texts(&block), ["ab", "cdef", "", "g"]);
+        assert_eq!(block.lines, 4);
+        assert_eq!(block.width, 40);
+        assert_eq!(block.height, 3 * 15 + 12);
+        assert_eq!(block.pieces.iter().map(|p| p.y).collect::<Vec<_>>(), [0., 15., 30., 45.]);
+    }
+
+    #[test]
+    fn crlf_newlines() {
+        let block = layout("ab\r\ncd\r\n", &TextLayout::new());
+        assert_eq!(texts(&block), ["ab", "cd", ""]);
+        assert_eq!(block.width, 20);
+    }
+
+    #[test]
+    fn word_wrapping() {
+        let mut text_layout = TextLayout::new();
+        text_layout.set_max_width(Some(70));
+        let block = layout("the quick  brown fox", &text_layout);
+        assert_eq!(texts(&block), ["the", "quick", "brown", "fox"]);
+
+        text_layout.set_max_width(Some(90));
+        let block = layout("the quick brown fox", &text_layout);
+        assert_eq!(texts(&block), ["the quick", "brown fox"]);
+        // The max width, even if the lines are shorter
+        assert_eq!(block.width, 90);
+    }
+
+    #[test]
+    fn long_words_split() {
+        let mut text_layout = TextLayout::new();
+        text_layout.set_max_width(Some(40));
+        let block = layout("a abcdefghij b", &text_layout);
+        assert_eq!(texts(&block), ["a", "abcd", "efgh", "ij b"]);
+
+        // One char wider than the max width : a char per line
+        text_layout.set_max_width(Some(5));
+        let block = layout("éà", &text_layout);
+        assert_eq!(texts(&block), ["é", "à"]);
+    }
+
+    #[test]
+    fn alignments() {
+        let mut text_layout = TextLayout::new();
+        text_layout.set_max_width(Some(100)).set_align(TextAlign::Center);
+        let block = layout("abcd\nabc", &text_layout);
+        assert_eq!(block.pieces.iter().map(|p| p.x).collect::<Vec<_>>(), [30., 35.]);
+
+        text_layout.set_align(TextAlign::Right);
+        let block = layout("abcd\nabc", &text_layout);
+        assert_eq!(block.pieces.iter().map(|p| p.x).collect::<Vec<_>>(), [60., 70.]);
+
+        // Without max width : in the widest line
+        let mut text_layout = TextLayout::new();
+        text_layout.set_align(TextAlign::Right);
+        let block = layout("abcd\nab", &text_layout);
+        assert_eq!(block.pieces.iter().map(|p| p.x).collect::<Vec<_>>(), [0., 20.]);
+    }
+
+    #[test]
+    fn justify() {
+        let mut text_layout = TextLayout::new();
+        text_layout.set_max_width(Some(100)).set_align(TextAlign::Justify);
+        let block = layout("aa bb cc dd\nee ff", &text_layout);
+
+        // "aa bb cc" (80) : words spread on 100, 20 left for 2 gaps
+        let pieces: Vec<(&str, Position)> = block.pieces.iter().map(|p| (p.text.as_str(), p.x)).collect();
+        assert_eq!(pieces, [
+            ("aa", 0.), ("bb", 40.), ("cc", 80.),
+            // Last lines of the paragraphs : left aligned
+            ("dd", 0.),
+            ("ee ff", 0.),
+        ]);
+        assert_eq!(block.lines, 3);
+    }
+
+    #[test]
+    fn line_spacing() {
+        let mut text_layout = TextLayout::new();
+        text_layout.set_line_spacing(1.5);
+        let block = layout("a\nb\nc", &text_layout);
+        assert_eq!(block.pieces.iter().map(|p| p.y).collect::<Vec<_>>(), [0., 23., 46.]);
+        assert_eq!(block.height, 2 * 23 + 12);
+
+        // No negative spacing
+        text_layout.set_line_spacing(-1.);
+        assert_eq!(text_layout.get_line_spacing(), 0.);
+        let block = layout("a\nb", &text_layout);
+        assert_eq!(block.height, 12);
+    }
+
+    #[test]
+    fn empty_text() {
+        let block = layout("", &TextLayout::new());
+        assert_eq!(block.lines, 1);
+        assert_eq!(block.width, 0);
+        assert_eq!(block.height, 12);
+    }
+}
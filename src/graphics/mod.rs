@@ -1,4 +1,14 @@
+pub mod bitmap_font;
+pub mod camera;
+pub mod canvas;
+#[allow(clippy::module_inception)]
 pub mod graphics;
 pub mod images;
+pub mod layout;
+pub mod renderer;
+pub mod rich_text;
+pub mod snapshot;
+pub mod view;
 pub mod color;
-pub mod fonts;
\ No newline at end of file
+pub mod fonts;
+pub mod window;
\ No newline at end of file
@@ -0,0 +1,347 @@
+/*================================================================
+ *                         R E N D E R E R
+ *
+ * Backend used by Graphics to draw. SdlCanvas is the SDL
+ * implementation, RecordingRenderer logs the draw commands.
+ *================================================================*/
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use sdl2::rect::{Point, Rect};
+use sdl2::render::Texture;
+use sdl2::video::Window;
+
+use crate::error::Game2dResult;
+use crate::game::common::{Angle, Size};
+use super::canvas::TexturesCreator;
+use super::color::Color;
+use super::graphics::DrawMode;
+
+/*
+ * TextureCopy : Copy of a texture (or a part of it) on the output
+ */
+pub struct TextureCopy<'a> {
+    pub texture: &'a Texture,
+    pub src: Option<Rect>,
+    pub dst: Rect,
+    pub angle: Angle,
+    pub center: Option<Point>,
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+}
+
+pub trait Renderer {
+
+    /*
+     * clear()
+     *
+     * @Brief : Clear the output with a color
+     */
+    fn clear(&mut self, color: Color);
+
+    /*
+     * present()
+     *
+     * @Brief : Show what has been drawn
+     */
+    fn present(&mut self);
+
+    /*
+     * line()
+     *
+     * @Brief : Draw a line
+     */
+    fn line(&mut self, start: Point, end: Point, color: Color) -> Game2dResult<()>;
+
+    /*
+     * rectangle()
+     *
+     * @Brief : Draw a rectangle
+     */
+    fn rectangle(&mut self, mode: DrawMode, rect: Rect, color: Color) -> Game2dResult<()>;
+
+    /*
+     * polygon()
+     *
+     * @Brief : Draw a closed polygon (rotated rectangles...)
+     */
+    fn polygon(&mut self, mode: DrawMode, points: &[Point], color: Color) -> Game2dResult<()>;
+
+    /*
+     * thick_line()
+     *
+     * @Brief : Draw a line wider than a pixel
+     */
+    fn thick_line(&mut self, start: Point, end: Point, width: u8, color: Color) -> Game2dResult<()>;
+
+    /*
+     * aa_line()
+     *
+     * @Brief : Draw an anti-aliased line
+     */
+    fn aa_line(&mut self, start: Point, end: Point, color: Color) -> Game2dResult<()>;
+
+    /*
+     * aa_polygon()
+     *
+     * @Brief : Draw the anti-aliased outline of a closed polygon
+     */
+    fn aa_polygon(&mut self, points: &[Point], color: Color) -> Game2dResult<()>;
+
+    /*
+     * texture()
+     *
+     * @Brief : Draw a textured quad (name : image of the texture)
+     */
+    fn texture(&mut self, name: &str, copy: &TextureCopy) -> Game2dResult<()>;
+
+    /*
+     * text()
+     *
+     * @Brief : Draw a text already rendered in a texture
+     */
+    fn text(&mut self, _text: &str, _color: Color, copy: &TextureCopy) -> Game2dResult<()> {
+        self.texture("", copy)
+    }
+
+    /*
+     * set_target()
+     *
+     * @Brief : Draw in a texture (name : render target of the
+     *          texture), None : draw in the output
+     */
+    fn set_target(&mut self, target: Option<(&str, &Texture)>) -> Game2dResult<()>;
+
+    /*
+     * textures_creator()
+     *
+     * @Brief : Create textures (images, fonts) for this renderer
+     */
+    fn textures_creator(&self) -> TexturesCreator;
+
+    /*
+     * output_size()
+     *
+     * @Brief : Size (width, height) of the output
+     */
+    fn output_size(&self) -> Game2dResult<(Size, Size)>;
+
+    /*
+     * read_pixels()
+     *
+     * @Brief : Read the pixels of the output (RGBA, 4 bytes per pixel)
+     */
+    fn read_pixels(&mut self) -> Game2dResult<Vec<u8>>;
+
+    /*
+     * set_view()
+     *
+     * @Brief : Draw in a part of the output (None : all the output)
+     *          with a scale (logical resolution)
+     */
+    fn set_view(&mut self, viewport: Option<Rect>, sx: f32, sy: f32) -> Game2dResult<()>;
+
+    /*
+     * window() / window_mut()
+     *
+     * @Brief : Window of the renderer (None when headless)
+     */
+    fn window(&self) -> Option<&Window> {
+        Option::None
+    }
+
+    fn window_mut(&mut self) -> Option<&mut Window> {
+        Option::None
+    }
+}
+
+/*================================================================
+ *                     D R A W   C O M M A N D
+ *================================================================*/
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand {
+    Clear(Color),
+    Present,
+    Line { start: Point, end: Point, color: Color },
+    Rectangle { mode: DrawMode, rect: Rect, color: Color },
+    Polygon { mode: DrawMode, points: Vec<Point>, color: Color },
+    ThickLine { start: Point, end: Point, width: u8, color: Color },
+    AaLine { start: Point, end: Point, color: Color },
+    AaPolygon { points: Vec<Point>, color: Color },
+    Texture { name: String, src: Option<Rect>, dst: Rect, angle: Angle, flip_horizontal: bool, flip_vertical: bool },
+    Text { text: String, color: Color, dst: Rect, angle: Angle },
+    View { viewport: Option<Rect>, sx: f32, sy: f32 },
+    Target(Option<String>),
+}
+
+/*
+ * DrawLog : Draw commands shared between a RecordingRenderer and the tests
+ */
+#[derive(Clone, Default)]
+pub struct DrawLog {
+    commands: Rc<RefCell<Vec<DrawCommand>>>,
+}
+
+impl DrawLog {
+    fn push(&self, command: DrawCommand) {
+        self.commands.borrow_mut().push(command);
+    }
+
+    /*
+     * get_commands()
+     *
+     * @Brief : All the commands logged
+     */
+    pub fn get_commands(&self) -> Vec<DrawCommand> {
+        self.commands.borrow().clone()
+    }
+
+    /*
+     * get_last_frame()
+     *
+     * @Brief : Commands logged since the last Clear
+     */
+    pub fn get_last_frame(&self) -> Vec<DrawCommand> {
+        let commands = self.commands.borrow();
+        let start = commands
+            .iter()
+            .rposition(|c| matches!(c, DrawCommand::Clear(_)))
+            .unwrap_or(0);
+        commands[start..].to_vec()
+    }
+
+    /*
+     * clear()
+     *
+     * @Brief : Forget the commands logged
+     */
+    pub fn clear(&self) {
+        self.commands.borrow_mut().clear();
+    }
+}
+
+/*================================================================
+ *                R E C O R D I N G   R E N D E R E R
+ *================================================================*/
+pub struct RecordingRenderer {
+    inner: Box<dyn Renderer>,
+    log: DrawLog,
+}
+
+impl RecordingRenderer {
+    /*
+     * new()
+     *
+     * @Brief : Log the commands then send them to the inner renderer
+     */
+    pub fn new(inner: Box<dyn Renderer>) -> RecordingRenderer {
+        RecordingRenderer {
+            inner,
+            log: DrawLog::default(),
+        }
+    }
+
+    /*
+     * get_log()
+     *
+     * @Brief : Handle on the commands logged (keep it before giving
+     *          the renderer to Graphics)
+     */
+    pub fn get_log(&self) -> DrawLog {
+        self.log.clone()
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn clear(&mut self, color: Color) {
+        self.log.push(DrawCommand::Clear(color));
+        self.inner.clear(color);
+    }
+
+    fn present(&mut self) {
+        self.log.push(DrawCommand::Present);
+        self.inner.present();
+    }
+
+    fn line(&mut self, start: Point, end: Point, color: Color) -> Game2dResult<()> {
+        self.log.push(DrawCommand::Line { start, end, color });
+        self.inner.line(start, end, color)
+    }
+
+    fn rectangle(&mut self, mode: DrawMode, rect: Rect, color: Color) -> Game2dResult<()> {
+        self.log.push(DrawCommand::Rectangle { mode, rect, color });
+        self.inner.rectangle(mode, rect, color)
+    }
+
+    fn polygon(&mut self, mode: DrawMode, points: &[Point], color: Color) -> Game2dResult<()> {
+        self.log.push(DrawCommand::Polygon { mode, points: points.to_vec(), color });
+        self.inner.polygon(mode, points, color)
+    }
+
+    fn thick_line(&mut self, start: Point, end: Point, width: u8, color: Color) -> Game2dResult<()> {
+        self.log.push(DrawCommand::ThickLine { start, end, width, color });
+        self.inner.thick_line(start, end, width, color)
+    }
+
+    fn aa_line(&mut self, start: Point, end: Point, color: Color) -> Game2dResult<()> {
+        self.log.push(DrawCommand::AaLine { start, end, color });
+        self.inner.aa_line(start, end, color)
+    }
+
+    fn aa_polygon(&mut self, points: &[Point], color: Color) -> Game2dResult<()> {
+        self.log.push(DrawCommand::AaPolygon { points: points.to_vec(), color });
+        self.inner.aa_polygon(points, color)
+    }
+
+    fn texture(&mut self, name: &str, copy: &TextureCopy) -> Game2dResult<()> {
+        self.log.push(DrawCommand::Texture {
+            name: name.to_string(),
+            src: copy.src,
+            dst: copy.dst,
+            angle: copy.angle,
+            flip_horizontal: copy.flip_horizontal,
+            flip_vertical: copy.flip_vertical,
+        });
+        self.inner.texture(name, copy)
+    }
+
+    fn text(&mut self, text: &str, color: Color, copy: &TextureCopy) -> Game2dResult<()> {
+        self.log.push(DrawCommand::Text {
+            text: text.to_string(),
+            color,
+            dst: copy.dst,
+            angle: copy.angle,
+        });
+        self.inner.text(text, color, copy)
+    }
+
+    fn set_target(&mut self, target: Option<(&str, &Texture)>) -> Game2dResult<()> {
+        self.log.push(DrawCommand::Target(target.map(|(name, _)| name.to_string())));
+        self.inner.set_target(target)
+    }
+
+    fn textures_creator(&self) -> TexturesCreator {
+        self.inner.textures_creator()
+    }
+
+    fn output_size(&self) -> Game2dResult<(Size, Size)> {
+        self.inner.output_size()
+    }
+
+    fn read_pixels(&mut self) -> Game2dResult<Vec<u8>> {
+        self.inner.read_pixels()
+    }
+
+    fn set_view(&mut self, viewport: Option<Rect>, sx: f32, sy: f32) -> Game2dResult<()> {
+        self.log.push(DrawCommand::View { viewport, sx, sy });
+        self.inner.set_view(viewport, sx, sy)
+    }
+
+    fn window(&self) -> Option<&Window> {
+        self.inner.window()
+    }
+
+    fn window_mut(&mut self) -> Option<&mut Window> {
+        self.inner.window_mut()
+    }
+}
@@ -0,0 +1,432 @@
+/*================================================================
+ *                       R I C H   T E X T
+ *
+ * Text with a small markup parsed in styled runs :
+ *
+ *   [color=red]...[/color]   color (name or #rrggbb / #rrggbbaa)
+ *   [b] [i] [u] [s]          bold, italic, underline, strikethrough
+ *   [wave] [shake]           glyphs effects
+ *   [[                       a '['
+ *
+ * Unknown tags are kept as text. A typewriter reveal shows the
+ * glyphs one after the other :
+ *
+ *   let mut dialog = RichText::parse("Hello [color=red][wave]world[/wave][/color]");
+ *   dialog.set_reveal_speed(Some(30.));
+ *   dialog.update(dt);
+ *   graphics.print_rich(&mut fonts_manager, &dialog, x, y, None)?;
+ *================================================================*/
+use std::str::FromStr;
+
+use crate::game::common::{DeltaTime, Position, Transformation};
+use super::color::Color;
+use super::fonts::FontStyle;
+
+pub const RICH_TEXT_DEFAULT_WAVE_AMPLITUDE: Position = 3.;
+pub const RICH_TEXT_DEFAULT_WAVE_SPEED: Transformation = 8.;
+pub const RICH_TEXT_DEFAULT_SHAKE: Position = 1.5;
+
+/*
+ * TextRun : a part of the text with the same style
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextRun {
+    pub text: String,
+    // None : color of the print
+    pub color: Option<Color>,
+    // Added to the style of the font
+    pub style: FontStyle,
+    pub wave: bool,
+    pub shake: bool,
+}
+
+impl TextRun {
+    /*
+     * has_effects()
+     *
+     * @Brief : The glyphs move (drawn one by one)
+     */
+    pub fn has_effects(&self) -> bool {
+        self.wave || self.shake
+    }
+}
+
+/*
+ * Markup : state of the tags opened while parsing
+ */
+#[derive(Default)]
+struct Markup {
+    colors: Vec<Color>,
+    bold: u32,
+    italic: u32,
+    underline: u32,
+    strikethrough: u32,
+    wave: u32,
+    shake: u32,
+}
+
+impl Markup {
+    /*
+     * apply()
+     *
+     * @Brief : Open or close a tag (false : not a tag)
+     */
+    fn apply(&mut self, tag: &str) -> bool {
+        let close = |count: &mut u32| *count = count.saturating_sub(1);
+        match tag {
+            "b"      => self.bold += 1,
+            "/b"     => close(&mut self.bold),
+            "i"      => self.italic += 1,
+            "/i"     => close(&mut self.italic),
+            "u"      => self.underline += 1,
+            "/u"     => close(&mut self.underline),
+            "s"      => self.strikethrough += 1,
+            "/s"     => close(&mut self.strikethrough),
+            "wave"   => self.wave += 1,
+            "/wave"  => close(&mut self.wave),
+            "shake"  => self.shake += 1,
+            "/shake" => close(&mut self.shake),
+            "/color" => { self.colors.pop(); },
+            _ => match tag.strip_prefix("color=").map(Color::from_str) {
+                Some(Ok(color)) => self.colors.push(color),
+                _ => return false,
+            },
+        }
+        true
+    }
+
+    fn run(&self, text: String) -> TextRun {
+        let mut style = FontStyle::NORMAL;
+        for (count, flag) in [
+            (self.bold, FontStyle::BOLD),
+            (self.italic, FontStyle::ITALIC),
+            (self.underline, FontStyle::UNDERLINE),
+            (self.strikethrough, FontStyle::STRIKETHROUGH),
+        ] {
+            if count > 0 {
+                style |= flag;
+            }
+        }
+        TextRun {
+            text,
+            color: self.colors.last().copied(),
+            style,
+            wave: self.wave > 0,
+            shake: self.shake > 0,
+        }
+    }
+}
+
+/*================================================================
+ *                      R I C H   T E X T
+ *================================================================*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct RichText {
+    runs: Vec<TextRun>,
+    chars: usize,
+
+    time: DeltaTime,
+    // Chars shown per second (None : all at once)
+    reveal_speed: Option<Transformation>,
+    revealed: bool,
+
+    wave_amplitude: Position,
+    wave_speed: Transformation,
+    shake: Position,
+}
+
+#[allow(dead_code)]
+impl RichText {
+    /*
+     * parse()
+     *
+     * @Brief : Parse a text with markup in runs
+     */
+    pub fn parse(markup: &str) -> RichText {
+        let mut state = Markup::default();
+        let mut runs = Vec::new();
+        let mut text = String::new();
+        let mut rest = markup;
+
+        while let Some(start) = rest.find('[') {
+            text.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            if let Some(after) = rest.strip_prefix("[[") {
+                text.push('[');
+                rest = after;
+                continue;
+            }
+
+            let tag = rest[1..].split_once(']').map(|(tag, after)| (tag.trim(), after));
+            match tag {
+                Some((tag, after)) => {
+                    let before = state.run(std::mem::take(&mut text));
+                    if state.apply(tag) {
+                        if !before.text.is_empty() {
+                            runs.push(before);
+                        }
+                    } else {
+                        // Unknown tag : kept as text
+                        text = before.text;
+                        text.push('[');
+                        text.push_str(&rest[1..rest.len() - after.len()]);
+                    }
+                    rest = after;
+                },
+                None => {
+                    text.push_str(rest);
+                    rest = "";
+                },
+            }
+        }
+        text.push_str(rest);
+        if !text.is_empty() {
+            runs.push(state.run(text));
+        }
+
+        let chars = runs.iter().map(|r| r.text.chars().count()).sum();
+        RichText {
+            runs,
+            chars,
+            time: 0.,
+            reveal_speed: Option::None,
+            revealed: false,
+            wave_amplitude: RICH_TEXT_DEFAULT_WAVE_AMPLITUDE,
+            wave_speed: RICH_TEXT_DEFAULT_WAVE_SPEED,
+            shake: RICH_TEXT_DEFAULT_SHAKE,
+        }
+    }
+
+    pub fn get_runs(&self) -> &[TextRun] {
+        &self.runs
+    }
+
+    /*
+     * get_text()
+     *
+     * @Brief : Text without the markup
+     */
+    pub fn get_text(&self) -> String {
+        self.runs.iter().map(|r| r.text.as_str()).collect()
+    }
+
+    /*
+     * len()
+     *
+     * @Brief : Number of chars (without the markup)
+     */
+    pub fn len(&self) -> usize {
+        self.chars
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars == 0
+    }
+
+    //=======================================================================
+    //                              EFFECTS
+    //=======================================================================
+    /*
+     * update()
+     *
+     * @Brief : Move the effects and the reveal forward
+     */
+    pub fn update(&mut self, dt: DeltaTime) {
+        self.time += dt;
+    }
+
+    /*
+     * set_wave()
+     *
+     * @Brief : Height (pixels) and speed of the wave
+     */
+    pub fn set_wave(&mut self, amplitude: Position, speed: Transformation) -> &mut Self {
+        self.wave_amplitude = amplitude;
+        self.wave_speed = speed;
+        self
+    }
+
+    /*
+     * set_shake()
+     *
+     * @Brief : Max offset (pixels) of the shaking glyphs
+     */
+    pub fn set_shake(&mut self, amplitude: Position) -> &mut Self {
+        self.shake = amplitude;
+        self
+    }
+
+    /*
+     * glyph_offset()
+     *
+     * @Brief : Offset of a glyph (index in the text) of a run
+     */
+    pub(crate) fn glyph_offset(&self, run: &TextRun, index: usize) -> (Position, Position) {
+        let (mut dx, mut dy) = (0., 0.);
+        if run.wave {
+            dy += self.wave_amplitude * (self.time * self.wave_speed - index as Transformation * 0.5).sin();
+        }
+        if run.shake {
+            // New offsets 30 times per second
+            let step = (self.time * 30.).floor();
+            dx += self.shake * (random(index as Transformation, step) * 2. - 1.);
+            dy += self.shake * (random(index as Transformation + 0.5, step) * 2. - 1.);
+        }
+        (dx, dy)
+    }
+
+    //=======================================================================
+    //                              REVEAL
+    //=======================================================================
+    /*
+     * set_reveal_speed()
+     *
+     * @Brief : Show the chars one after the other (chars per second,
+     *          None : all at once) from now
+     */
+    pub fn set_reveal_speed(&mut self, reveal_speed: Option<Transformation>) -> &mut Self {
+        self.reveal_speed = reveal_speed;
+        self.restart();
+        self
+    }
+
+    /*
+     * restart()
+     *
+     * @Brief : Reveal the text again from the start
+     */
+    pub fn restart(&mut self) {
+        self.time = 0.;
+        self.revealed = false;
+    }
+
+    /*
+     * reveal_all()
+     *
+     * @Brief : Show all the chars now (dialog skipped)
+     */
+    pub fn reveal_all(&mut self) {
+        self.revealed = true;
+    }
+
+    pub fn get_visible_chars(&self) -> usize {
+        match self.reveal_speed {
+            Some(speed) if !self.revealed => ((self.time * speed).max(0.) as usize).min(self.chars),
+            _ => self.chars,
+        }
+    }
+
+    pub fn is_revealed(&self) -> bool {
+        self.get_visible_chars() == self.chars
+    }
+}
+
+/*
+ * random()
+ *
+ * @Brief : Pseudo random value between 0 and 1
+ */
+fn random(seed: Transformation, step: Transformation) -> Transformation {
+    ((seed * 12.9898 + step * 78.233).sin() * 43758.547).fract().abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(markup: &str) -> Vec<(String, Option<Color>, FontStyle)> {
+        RichText::parse(markup)
+            .get_runs()
+            .iter()
+            .map(|r| (r.text.clone(), r.color, r.style))
+            .collect()
+    }
+
+    fn run(text: &str, color: Option<Color>, style: FontStyle) -> (String, Option<Color>, FontStyle) {
+        (text.to_string(), color, style)
+    }
+
+    #[test]
+    fn parse_nested_styles() {
+        assert_eq!(runs("a[b]b[i]c[/b]d[/i]e"), [
+            run("a", Option::None, FontStyle::NORMAL),
+            run("b", Option::None, FontStyle::BOLD),
+            run("c", Option::None, FontStyle::BOLD | FontStyle::ITALIC),
+            run("d", Option::None, FontStyle::ITALIC),
+            run("e", Option::None, FontStyle::NORMAL),
+        ]);
+    }
+
+    #[test]
+    fn parse_nested_colors() {
+        assert_eq!(runs("[color=red]r[color=#00ff00]g[/color]r[/color]n"), [
+            run("r", Some(Color::RED), FontStyle::NORMAL),
+            run("g", Some(Color::GREEN), FontStyle::NORMAL),
+            run("r", Some(Color::RED), FontStyle::NORMAL),
+            run("n", Option::None, FontStyle::NORMAL),
+        ]);
+    }
+
+    #[test]
+    fn parse_effects() {
+        let text = RichText::parse("[wave]w[shake]ws[/wave]s[/shake]");
+        let effects: Vec<(bool, bool)> = text.get_runs().iter().map(|r| (r.wave, r.shake)).collect();
+        assert_eq!(effects, [(true, false), (true, true), (false, true)]);
+        assert!(text.get_runs().iter().all(TextRun::has_effects));
+    }
+
+    #[test]
+    fn parse_escaped_bracket() {
+        assert_eq!(runs("a[[b]"), [run("a[b]", Option::None, FontStyle::NORMAL)]);
+        assert_eq!(RichText::parse("[[[b]x").get_text(), "[x");
+    }
+
+    #[test]
+    fn parse_unknown_tags_kept() {
+        assert_eq!(runs("x[foo]y[color=nope]z"), [run("x[foo]y[color=nope]z", Option::None, FontStyle::NORMAL)]);
+    }
+
+    #[test]
+    fn parse_unclosed_bracket() {
+        assert_eq!(runs("a[b"), [run("a[b", Option::None, FontStyle::NORMAL)]);
+        assert_eq!(runs("a[b]c["), [
+            run("a", Option::None, FontStyle::NORMAL),
+            run("c[", Option::None, FontStyle::BOLD),
+        ]);
+    }
+
+    #[test]
+    fn text_without_markup() {
+        let text = RichText::parse("[b]hé[/b]\nllo");
+        assert_eq!(text.get_text(), "hé\nllo");
+        assert_eq!(text.len(), 6);
+        assert!(!text.is_empty());
+        assert!(RichText::parse("[b][/b]").is_empty());
+    }
+
+    #[test]
+    fn visible_chars() {
+        let mut text = RichText::parse("[b]hello[/b] world");
+        assert_eq!(text.get_visible_chars(), 11);
+        assert!(text.is_revealed());
+
+        // 10 chars per second
+        text.set_reveal_speed(Some(10.));
+        assert_eq!(text.get_visible_chars(), 0);
+        text.update(0.25);
+        assert_eq!(text.get_visible_chars(), 2);
+        assert!(!text.is_revealed());
+        text.update(10.);
+        assert_eq!(text.get_visible_chars(), 11);
+        assert!(text.is_revealed());
+
+        text.restart();
+        assert_eq!(text.get_visible_chars(), 0);
+        text.reveal_all();
+        assert_eq!(text.get_visible_chars(), 11);
+
+        text.set_reveal_speed(Option::None);
+        assert_eq!(text.get_visible_chars(), 11);
+    }
+}
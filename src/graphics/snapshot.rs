@@ -0,0 +1,190 @@
+/*================================================================
+ *                        S N A P S H O T
+ *
+ * Golden-image testing : render a frame with Graphics (headless)
+ * and compare it with a reference PNG.
+ *================================================================*/
+use std::path::{Path, PathBuf};
+
+use sdl2::image::{LoadSurface, SaveSurface};
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::surface::Surface;
+
+use crate::error::{Game2dError, Game2dResult};
+use crate::game::common::{ColorT, Size};
+use super::graphics::Graphics;
+
+/*
+ * Set this variable to rewrite the references with the frames rendered
+ */
+pub const SNAPSHOT_UPDATE_ENV: &str = "GAME2D_UPDATE_SNAPSHOTS";
+
+pub struct Snapshot {
+    reference: PathBuf,
+    tolerance: ColorT,
+}
+
+#[allow(dead_code)]
+impl Snapshot {
+    /*
+     * new()
+     *
+     * @Brief : Snapshot compared with the reference PNG
+     */
+    pub fn new(reference: &str) -> Snapshot {
+        Snapshot {
+            reference: PathBuf::from(reference),
+            tolerance: 0,
+        }
+    }
+
+    /*
+     * set_tolerance()
+     *
+     * @Brief : Max difference accepted on each channel of a pixel
+     */
+    pub fn set_tolerance(&mut self, tolerance: ColorT) -> &mut Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /*
+     * get_diff_path()
+     *
+     * @Brief : Where the diff image is written on a mismatch
+     */
+    pub fn get_diff_path(&self) -> PathBuf {
+        self.reference.with_extension("diff.png")
+    }
+
+    /*
+     * get_actual_path()
+     *
+     * @Brief : Where the frame rendered is written on a mismatch
+     */
+    pub fn get_actual_path(&self) -> PathBuf {
+        self.reference.with_extension("actual.png")
+    }
+
+    /*
+     * check()
+     *
+     * @Brief : Render a frame and compare it with the reference.
+     *          If SNAPSHOT_UPDATE_ENV is set, the frame becomes the
+     *          reference. A missing reference is an error.
+     */
+    pub fn check<F>(&self, graphics: &mut Graphics, draw: F) -> Game2dResult<()>
+    where
+        F: FnOnce(&mut Graphics) -> Game2dResult<()>,
+    {
+        graphics.begin_draw();
+        let drawn = draw(graphics);
+        let actual = graphics.read_pixels();
+        graphics.end_draw();
+
+        drawn?;
+        let actual = actual?;
+        let (width, height) = graphics
+            .get_output_size()
+            .ok_or_else(|| Game2dError::Render(String::from("Can't get the output size")))?;
+
+        if std::env::var_os(SNAPSHOT_UPDATE_ENV).is_some() {
+            return save_png(&self.reference, actual, width, height);
+        }
+        if !self.reference.exists() {
+            save_png(&self.get_actual_path(), actual, width, height)?;
+            return Err(Game2dError::Snapshot(format!(
+                "Snapshot {} : no reference, frame written in {} ({}=1 to record it)",
+                self.reference.display(), self.get_actual_path().display(), SNAPSHOT_UPDATE_ENV
+            )));
+        }
+
+        let (expected, expected_width, expected_height) = load_png(&self.reference)?;
+        if expected_width != width || expected_height != height {
+            save_png(&self.get_actual_path(), actual, width, height)?;
+            return Err(Game2dError::Snapshot(format!(
+                "Snapshot {} : size {}x{} expected, {}x{} rendered",
+                self.reference.display(), expected_width, expected_height, width, height
+            )));
+        }
+
+        let mut diff = Vec::with_capacity(actual.len());
+        let mut mismatches = 0;
+        for (a, e) in actual.chunks_exact(4).zip(expected.chunks_exact(4)) {
+            let over = a.iter().zip(e.iter()).any(|(a, e)| a.abs_diff(*e) > self.tolerance);
+            if over {
+                mismatches += 1;
+                diff.extend_from_slice(&[255, 0, 0, 255]);
+            } else {
+                // Dimmed grey of the actual pixel
+                let grey = ((a[0] as u32 + a[1] as u32 + a[2] as u32) / 12) as u8;
+                diff.extend_from_slice(&[grey, grey, grey, 255]);
+            }
+        }
+
+        if mismatches == 0 {
+            return Ok(());
+        }
+
+        save_png(&self.get_diff_path(), diff, width, height)?;
+        save_png(&self.get_actual_path(), actual, width, height)?;
+
+        Err(Game2dError::Snapshot(format!(
+            "Snapshot {} : {} pixel(s) differ (tolerance {}), diff written in {}",
+            self.reference.display(), mismatches, self.tolerance, self.get_diff_path().display()
+        )))
+    }
+}
+
+/*
+ * assert_snapshot()
+ *
+ * @Brief : Panic if the frame rendered doesn't match the reference
+ */
+pub fn assert_snapshot<F>(graphics: &mut Graphics, reference: &str, tolerance: ColorT, draw: F)
+where
+    F: FnOnce(&mut Graphics) -> Game2dResult<()>,
+{
+    if let Err(e) = Snapshot::new(reference).set_tolerance(tolerance).check(graphics, draw) {
+        panic!("{}", e);
+    }
+}
+
+/*
+ * save_png()
+ *
+ * @Brief : Write RGBA pixels in a PNG
+ */
+fn save_png(path: &Path, mut pixels: Vec<u8>, width: Size, height: Size) -> Game2dResult<()> {
+    let path_name = path.display().to_string();
+    let surface = Surface::from_data(&mut pixels, width, height, width * 4, PixelFormatEnum::RGBA32)
+        .map_err(Game2dError::Snapshot)?;
+    surface
+        .save(path)
+        .map_err(|message| Game2dError::AssetIo { path: path_name, message })
+}
+
+/*
+ * load_png()
+ *
+ * @Brief : Read a PNG as RGBA pixels
+ */
+fn load_png(path: &Path) -> Game2dResult<(Vec<u8>, Size, Size)> {
+    let path_name = path.display().to_string();
+    let surface = Surface::from_file(path)
+        .and_then(|s| s.convert_format(PixelFormatEnum::RGBA32))
+        .map_err(|e| Game2dError::from_asset(&path_name, e))?;
+    let (width, height) = (surface.width(), surface.height());
+    let pitch = surface.pitch() as usize;
+    let row = (width * 4) as usize;
+
+    let pixels = surface.with_lock(|data| {
+        let mut pixels = Vec::with_capacity(row * height as usize);
+        for y in 0..height as usize {
+            pixels.extend_from_slice(&data[y * pitch..y * pitch + row]);
+        }
+        pixels
+    });
+
+    Ok((pixels, width, height))
+}
@@ -0,0 +1,154 @@
+/*================================================================
+ *                            V I E W
+ *
+ * Logical resolution : the game draws in a fixed size, scaled to
+ * the output (window) with a policy.
+ *================================================================*/
+use sdl2::rect::Rect;
+
+use crate::game::common::{Position, Size, Transformation};
+
+/*
+ * ScalePolicy : how the logical size fills the output
+ */
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum ScalePolicy {
+    // Same scale on both axes, bars on the sides
+    Letterbox,
+    // Fill the output, the aspect ratio isn't kept
+    Stretch,
+    // Largest integer scale that fits (pixel art), bars on the sides
+    IntegerScale,
+}
+
+/*
+ * LogicalView : where the logical size is drawn on the output
+ */
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct LogicalView {
+    pub viewport: Rect,
+    pub sx: Transformation,
+    pub sy: Transformation,
+}
+
+impl LogicalView {
+    /*
+     * new()
+     *
+     * @Brief : View of a logical size on an output size
+     */
+    pub fn new(output: (Size, Size), logical: (Size, Size), policy: ScalePolicy) -> LogicalView {
+        let (ow, oh) = (output.0 as Transformation, output.1 as Transformation);
+        let (lw, lh) = (logical.0.max(1) as Transformation, logical.1.max(1) as Transformation);
+
+        let (sx, sy) = match policy {
+            ScalePolicy::Stretch => (ow / lw, oh / lh),
+            ScalePolicy::Letterbox => {
+                let scale = (ow / lw).min(oh / lh);
+                (scale, scale)
+            },
+            ScalePolicy::IntegerScale => {
+                let scale = (ow / lw).min(oh / lh).floor().max(1.);
+                (scale, scale)
+            },
+        };
+
+        let (width, height) = ((lw * sx).round(), (lh * sy).round());
+        LogicalView {
+            viewport: Rect::new(
+                ((ow - width) / 2.).floor() as i32,
+                ((oh - height) / 2.).floor() as i32,
+                width as u32,
+                height as u32,
+            ),
+            sx,
+            sy,
+        }
+    }
+
+    /*
+     * to_logical()
+     *
+     * @Brief : Output position to logical position (outside the logical
+     *          size in the bars)
+     */
+    pub fn to_logical(&self, x: Position, y: Position) -> (Position, Position) {
+        (
+            (x - self.viewport.x() as Position) / self.sx,
+            (y - self.viewport.y() as Position) / self.sy,
+        )
+    }
+
+    /*
+     * to_output()
+     *
+     * @Brief : Logical position to output position
+     */
+    pub fn to_output(&self, x: Position, y: Position) -> (Position, Position) {
+        (
+            x * self.sx + self.viewport.x() as Position,
+            y * self.sy + self.viewport.y() as Position,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOGICAL: (Size, Size) = (320, 180);
+
+    #[test]
+    fn letterbox_wider_output() {
+        let view = LogicalView::new((800, 360), LOGICAL, ScalePolicy::Letterbox);
+        assert_eq!(view.viewport, Rect::new(80, 0, 640, 360));
+        assert_eq!((view.sx, view.sy), (2., 2.));
+    }
+
+    #[test]
+    fn letterbox_taller_output() {
+        let view = LogicalView::new((640, 600), LOGICAL, ScalePolicy::Letterbox);
+        assert_eq!(view.viewport, Rect::new(0, 120, 640, 360));
+        assert_eq!((view.sx, view.sy), (2., 2.));
+    }
+
+    #[test]
+    fn integer_scale_rounded_down() {
+        // 3.125 on x, 3.33 on y
+        let view = LogicalView::new((1000, 600), LOGICAL, ScalePolicy::IntegerScale);
+        assert_eq!(view.viewport, Rect::new(20, 30, 960, 540));
+        assert_eq!((view.sx, view.sy), (3., 3.));
+
+        // At least 1 : larger than the output
+        let view = LogicalView::new((200, 100), LOGICAL, ScalePolicy::IntegerScale);
+        assert_eq!(view.viewport, Rect::new(-60, -40, 320, 180));
+        assert_eq!((view.sx, view.sy), (1., 1.));
+    }
+
+    #[test]
+    fn stretch() {
+        let view = LogicalView::new((800, 720), LOGICAL, ScalePolicy::Stretch);
+        assert_eq!(view.viewport, Rect::new(0, 0, 800, 720));
+        assert_eq!((view.sx, view.sy), (2.5, 4.));
+        assert_eq!(view.to_logical(400., 360.), (160., 90.));
+    }
+
+    #[test]
+    fn positions_in_the_bars() {
+        // Bars on the left and the right
+        let view = LogicalView::new((800, 360), LOGICAL, ScalePolicy::Letterbox);
+        assert_eq!(view.to_logical(80., 0.), (0., 0.));
+        assert_eq!(view.to_logical(40., 100.), (-20., 50.));
+        assert_eq!(view.to_logical(760., 359.), (340., 179.5));
+
+        // Bars on the top and the bottom
+        let view = LogicalView::new((640, 600), LOGICAL, ScalePolicy::Letterbox);
+        assert_eq!(view.to_logical(10., 60.), (5., -30.));
+        assert_eq!(view.to_logical(10., 540.), (5., 210.));
+
+        for (x, y) in [(10., 60.), (320., 300.), (639., 599.)] {
+            let (lx, ly) = view.to_logical(x, y);
+            assert_eq!(view.to_output(lx, ly), (x, y));
+        }
+    }
+}
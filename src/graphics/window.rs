@@ -0,0 +1,136 @@
+/*================================================================
+ *                          W I N D O W
+ *
+ * Window settings, fullscreen modes and window events.
+ *================================================================*/
+use sdl2::event::{Event, WindowEvent};
+use sdl2::video::FullscreenType;
+
+use crate::game::common::Size;
+
+/*
+ * FullscreenMode
+ */
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum FullscreenMode {
+    Windowed,
+    // Fullscreen window at the desktop resolution
+    Borderless,
+    // Change the display resolution
+    Exclusive,
+}
+
+impl FullscreenMode {
+    pub(crate) fn to_sdl_fullscreen(self) -> FullscreenType {
+        match self {
+            FullscreenMode::Windowed   => FullscreenType::Off,
+            FullscreenMode::Borderless => FullscreenType::Desktop,
+            FullscreenMode::Exclusive  => FullscreenType::True,
+        }
+    }
+
+    pub(crate) fn from_sdl_fullscreen(fullscreen: FullscreenType) -> FullscreenMode {
+        match fullscreen {
+            FullscreenType::Off     => FullscreenMode::Windowed,
+            FullscreenType::Desktop => FullscreenMode::Borderless,
+            FullscreenType::True    => FullscreenMode::Exclusive,
+        }
+    }
+}
+
+/*
+ * WindowEvents : what happened to the window
+ */
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum WindowEvents {
+    // New size of the window (pixels)
+    Resized(Size, Size),
+    FocusGained,
+    FocusLost,
+    Minimized,
+    Maximized,
+    Restored,
+}
+
+impl WindowEvents {
+    /*
+     * from_sdl_event()
+     *
+     * @Brief : Bind SDL window events to Game2D WindowEvents
+     */
+    pub(crate) fn from_sdl_event(event: &Event) -> Option<WindowEvents> {
+        match event {
+            Event::Window { win_event, .. } => match win_event {
+                WindowEvent::SizeChanged(w, h) => Some(WindowEvents::Resized((*w).max(0) as Size, (*h).max(0) as Size)),
+                WindowEvent::FocusGained       => Some(WindowEvents::FocusGained),
+                WindowEvent::FocusLost         => Some(WindowEvents::FocusLost),
+                WindowEvent::Minimized         => Some(WindowEvents::Minimized),
+                WindowEvent::Maximized         => Some(WindowEvents::Maximized),
+                WindowEvent::Restored          => Some(WindowEvents::Restored),
+                _                              => Option::None,
+            },
+            _ => Option::None,
+        }
+    }
+}
+
+/*================================================================
+ *                 W I N D O W   S E T T I N G S
+ *================================================================*/
+#[derive(PartialEq, Debug, Clone)]
+pub struct WindowSettings {
+    pub(crate) title: String,
+    pub(crate) width: Size,
+    pub(crate) height: Size,
+    pub(crate) fullscreen: FullscreenMode,
+    pub(crate) resizable: bool,
+    pub(crate) vsync: bool,
+    pub(crate) icon: Option<String>,
+}
+
+#[allow(dead_code)]
+impl WindowSettings {
+    pub fn new(title: &str, width: Size, height: Size) -> WindowSettings {
+        WindowSettings {
+            title: title.to_string(),
+            width,
+            height,
+            fullscreen: FullscreenMode::Windowed,
+            resizable: false,
+            vsync: false,
+            icon: Option::None,
+        }
+    }
+
+    pub fn set_fullscreen(&mut self, fullscreen: FullscreenMode) -> &mut Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    pub fn set_resizable(&mut self, resizable: bool) -> &mut Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /*
+     * set_vsync()
+     *
+     * @Brief : Wait for the screen refresh when presenting a frame.
+     *          Only used when the window is created, it can't change
+     *          after (see Graphics::is_vsync)
+     */
+    pub fn set_vsync(&mut self, vsync: bool) -> &mut Self {
+        self.vsync = vsync;
+        self
+    }
+
+    /*
+     * set_icon()
+     *
+     * @Brief : Image file of the window icon
+     */
+    pub fn set_icon(&mut self, filename: &str) -> &mut Self {
+        self.icon = Some(filename.to_string());
+        self
+    }
+}
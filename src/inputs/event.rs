@@ -0,0 +1,265 @@
+/*================================================================
+ *                           E V E N T
+ *
+ * Input events processed by the game, built from the SDL events
+ * (or read from a recording) and applied to the Inputs.
+ *
+ * Text format (one event per line, see InputRecording) :
+ *
+ *   KeyDown <key> <scancode> <modifiers>     KeyDown Space Space C
+ *   KeyUp <key> <scancode> <modifiers>
+ *   MouseMotion <x> <y> <xrel> <yrel>
+ *   MouseDown <button> <x> <y>
+ *   MouseUp <button> <x> <y>
+ *   MouseWheel <x> <y>
+ *   Text <text>
+ *   Editing <start> <length> <text>
+ *   PadAdded <id> <name>
+ *   PadRemoved <id>
+ *   PadDown <id> <button>
+ *   PadUp <id> <button>
+ *   PadAxis <id> <axis> <value>
+ *   Quit
+ *
+ * "-" : unknown key / button, modifiers are letters among "CSAG"
+ * (Ctrl, Shift, Alt, Gui), texts escape '\', '\n' and '\r'.
+ *================================================================*/
+use std::fmt;
+use std::str::FromStr;
+
+use sdl2::event::Event;
+use sdl2::mouse::MouseWheelDirection;
+
+use crate::game::common::Position;
+use super::gamepad::{GamepadAxes, GamepadButtons, GamepadId};
+use super::input_map::AxisValue;
+use super::keyboard::{Keyboard, Keys, Modifiers};
+use super::mouse::{MouseButtons, WheelDelta};
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum InputEvent {
+    KeyDown { key: Option<Keys>, scancode: Option<Keys>, modifiers: Modifiers },
+    KeyUp { key: Option<Keys>, scancode: Option<Keys>, modifiers: Modifiers },
+    MouseMotion { x: Position, y: Position, xrel: Position, yrel: Position },
+    MouseButtonDown { button: Option<MouseButtons>, x: Position, y: Position },
+    MouseButtonUp { button: Option<MouseButtons>, x: Position, y: Position },
+    MouseWheel { x: WheelDelta, y: WheelDelta },
+    TextInput(String),
+    TextEditing { text: String, start: usize, length: usize },
+    GamepadConnected { id: GamepadId, name: String },
+    GamepadDisconnected(GamepadId),
+    GamepadButtonDown { id: GamepadId, button: GamepadButtons },
+    GamepadButtonUp { id: GamepadId, button: GamepadButtons },
+    GamepadAxis { id: GamepadId, axis: GamepadAxes, value: AxisValue },
+    Quit,
+}
+
+impl InputEvent {
+    /*
+     * from_sdl_event()
+     *
+     * @Brief : Input event of an SDL event (None : not an input, or
+     *          handled by the game like gamepads connected)
+     */
+    pub(crate) fn from_sdl_event(event: &Event) -> Option<InputEvent> {
+        match event {
+            Event::KeyDown { keycode, scancode, keymod, .. } => Some(InputEvent::KeyDown {
+                key: keycode.and_then(Keyboard::_sdl_keycode_to_key),
+                scancode: scancode.and_then(Keyboard::_sdl_scancode_to_key),
+                modifiers: Modifiers::from_sdl_mod(*keymod),
+            }),
+            Event::KeyUp { keycode, scancode, keymod, .. } => Some(InputEvent::KeyUp {
+                key: keycode.and_then(Keyboard::_sdl_keycode_to_key),
+                scancode: scancode.and_then(Keyboard::_sdl_scancode_to_key),
+                modifiers: Modifiers::from_sdl_mod(*keymod),
+            }),
+            Event::MouseMotion { x, y, xrel, yrel, .. } => Some(InputEvent::MouseMotion {
+                x: *x as Position,
+                y: *y as Position,
+                xrel: *xrel as Position,
+                yrel: *yrel as Position,
+            }),
+            Event::MouseButtonDown { mouse_btn, x, y, .. } => Some(InputEvent::MouseButtonDown {
+                button: MouseButtons::from_sdl_button(*mouse_btn),
+                x: *x as Position,
+                y: *y as Position,
+            }),
+            Event::MouseButtonUp { mouse_btn, x, y, .. } => Some(InputEvent::MouseButtonUp {
+                button: MouseButtons::from_sdl_button(*mouse_btn),
+                x: *x as Position,
+                y: *y as Position,
+            }),
+            Event::MouseWheel { x, y, direction, .. } => {
+                let sign = if *direction == MouseWheelDirection::Flipped { -1. } else { 1. };
+                Some(InputEvent::MouseWheel { x: *x as WheelDelta * sign, y: *y as WheelDelta * sign })
+            },
+            Event::TextInput { text, .. } => Some(InputEvent::TextInput(text.clone())),
+            Event::TextEditing { text, start, length, .. } => Some(InputEvent::TextEditing {
+                text: text.clone(),
+                start: (*start).max(0) as usize,
+                length: (*length).max(0) as usize,
+            }),
+            Event::ControllerDeviceRemoved { which, .. } => Some(InputEvent::GamepadDisconnected(*which)),
+            Event::Quit { .. } => Some(InputEvent::Quit),
+            _ => Option::None,
+        }
+    }
+}
+
+impl fmt::Display for InputEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputEvent::KeyDown { key, scancode, modifiers } => {
+                write!(f, "KeyDown {} {} {}", optional(key), optional(scancode), modifiers_to_string(modifiers))
+            },
+            InputEvent::KeyUp { key, scancode, modifiers } => {
+                write!(f, "KeyUp {} {} {}", optional(key), optional(scancode), modifiers_to_string(modifiers))
+            },
+            InputEvent::MouseMotion { x, y, xrel, yrel } => write!(f, "MouseMotion {} {} {} {}", x, y, xrel, yrel),
+            InputEvent::MouseButtonDown { button, x, y } => write!(f, "MouseDown {} {} {}", optional(button), x, y),
+            InputEvent::MouseButtonUp { button, x, y } => write!(f, "MouseUp {} {} {}", optional(button), x, y),
+            InputEvent::MouseWheel { x, y } => write!(f, "MouseWheel {} {}", x, y),
+            InputEvent::TextInput(text) => write!(f, "Text {}", escape(text)),
+            InputEvent::TextEditing { text, start, length } => write!(f, "Editing {} {} {}", start, length, escape(text)),
+            InputEvent::GamepadConnected { id, name } => write!(f, "PadAdded {} {}", id, escape(name)),
+            InputEvent::GamepadDisconnected(id) => write!(f, "PadRemoved {}", id),
+            InputEvent::GamepadButtonDown { id, button } => write!(f, "PadDown {} {}", id, button),
+            InputEvent::GamepadButtonUp { id, button } => write!(f, "PadUp {} {}", id, button),
+            InputEvent::GamepadAxis { id, axis, value } => write!(f, "PadAxis {} {} {}", id, axis, value),
+            InputEvent::Quit => write!(f, "Quit"),
+        }
+    }
+}
+
+impl FromStr for InputEvent {
+    type Err = String;
+
+    fn from_str(event: &str) -> Result<Self, Self::Err> {
+        let (kind, args) = event.split_once(' ').unwrap_or((event, ""));
+        let bad = |_| format!("Bad event {}", event);
+
+        // Events ending with a text (may contain spaces)
+        match kind {
+            "Text" => return Ok(InputEvent::TextInput(unescape(args))),
+            "Editing" => {
+                let mut parts = args.splitn(3, ' ');
+                let start = parts.next().unwrap_or("").parse().map_err(bad)?;
+                let length = parts.next().unwrap_or("").parse().map_err(bad)?;
+                let text = unescape(parts.next().unwrap_or(""));
+                return Ok(InputEvent::TextEditing { text, start, length });
+            },
+            "PadAdded" => {
+                let (id, name) = args.split_once(' ').unwrap_or((args, ""));
+                return Ok(InputEvent::GamepadConnected { id: id.parse().map_err(bad)?, name: unescape(name) });
+            },
+            _ => {},
+        }
+
+        let args: Vec<&str> = args.split_whitespace().collect();
+        let bad = |_| format!("Bad event {}", event);
+        let bad_str = |_: strum::ParseError| format!("Bad event {}", event);
+        let float = |s: &str| s.parse::<f32>().map_err(|_| format!("Bad event {}", event));
+
+        match (kind, args.as_slice()) {
+            ("KeyDown", [key, scancode, modifiers]) => Ok(InputEvent::KeyDown {
+                key: parse_optional(key).map_err(bad_str)?,
+                scancode: parse_optional(scancode).map_err(bad_str)?,
+                modifiers: modifiers_from_str(modifiers),
+            }),
+            ("KeyUp", [key, scancode, modifiers]) => Ok(InputEvent::KeyUp {
+                key: parse_optional(key).map_err(bad_str)?,
+                scancode: parse_optional(scancode).map_err(bad_str)?,
+                modifiers: modifiers_from_str(modifiers),
+            }),
+            ("MouseMotion", [x, y, xrel, yrel]) => Ok(InputEvent::MouseMotion {
+                x: float(x)?,
+                y: float(y)?,
+                xrel: float(xrel)?,
+                yrel: float(yrel)?,
+            }),
+            ("MouseDown", [button, x, y]) => Ok(InputEvent::MouseButtonDown {
+                button: parse_optional(button).map_err(bad_str)?,
+                x: float(x)?,
+                y: float(y)?,
+            }),
+            ("MouseUp", [button, x, y]) => Ok(InputEvent::MouseButtonUp {
+                button: parse_optional(button).map_err(bad_str)?,
+                x: float(x)?,
+                y: float(y)?,
+            }),
+            ("MouseWheel", [x, y]) => Ok(InputEvent::MouseWheel { x: float(x)?, y: float(y)? }),
+            ("PadRemoved", [id]) => Ok(InputEvent::GamepadDisconnected(id.parse().map_err(bad)?)),
+            ("PadDown", [id, button]) => Ok(InputEvent::GamepadButtonDown {
+                id: id.parse().map_err(bad)?,
+                button: GamepadButtons::from_str(button).map_err(bad_str)?,
+            }),
+            ("PadUp", [id, button]) => Ok(InputEvent::GamepadButtonUp {
+                id: id.parse().map_err(bad)?,
+                button: GamepadButtons::from_str(button).map_err(bad_str)?,
+            }),
+            ("PadAxis", [id, axis, value]) => Ok(InputEvent::GamepadAxis {
+                id: id.parse().map_err(bad)?,
+                axis: GamepadAxes::from_str(axis).map_err(bad_str)?,
+                value: float(value)?,
+            }),
+            ("Quit", []) => Ok(InputEvent::Quit),
+            _ => Err(format!("Unknown event {}", event)),
+        }
+    }
+}
+
+fn optional<T: fmt::Display>(value: &Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => String::from("-"),
+    }
+}
+
+fn parse_optional<T: FromStr>(value: &str) -> Result<Option<T>, T::Err> {
+    if value == "-" {
+        Ok(Option::None)
+    } else {
+        T::from_str(value).map(Some)
+    }
+}
+
+fn modifiers_to_string(modifiers: &Modifiers) -> String {
+    let mut flags = String::new();
+    if modifiers.ctrl { flags.push('C'); }
+    if modifiers.shift { flags.push('S'); }
+    if modifiers.alt { flags.push('A'); }
+    if modifiers.gui { flags.push('G'); }
+    if flags.is_empty() { flags.push('-'); }
+    flags
+}
+
+fn modifiers_from_str(flags: &str) -> Modifiers {
+    Modifiers {
+        shift: flags.contains('S'),
+        ctrl: flags.contains('C'),
+        alt: flags.contains('A'),
+        gui: flags.contains('G'),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
@@ -0,0 +1,668 @@
+/*================================================================
+ *                         G A M E P A D
+ *
+ * Game controllers (SDL GameController API) : buttons, triggers
+ * and sticks of each player, hot-plug, and virtual controllers
+ * to test without hardware.
+ *================================================================*/
+use std::collections::HashMap;
+
+use sdl2::controller::{Axis, Button, GameController};
+use sdl2::GameControllerSubsystem;
+use strum::{EnumCount, IntoEnumIterator};
+use strum_macros::{AsRefStr, Display, EnumCount, EnumIter, EnumString};
+
+use crate::error::{Game2dError, Game2dResult};
+use super::event::InputEvent;
+use super::input_map::AxisValue;
+
+pub type PlayerId = usize;
+pub type GamepadId = u32;
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, AsRefStr, Display, EnumCount, EnumIter, EnumString)]
+pub enum GamepadButtons {
+    A,
+    B,
+    X,
+    Y,
+    Back,
+    Guide,
+    Start,
+    LeftStick,
+    RightStick,
+    LeftShoulder,
+    RightShoulder,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+impl GamepadButtons {
+    /*
+     * to_sdl_button()
+     *
+     * @Brief : Bind Game2D GamepadButtons to SDL Button
+     */
+    pub(crate) fn to_sdl_button(self) -> Button {
+        match self {
+            GamepadButtons::A             => Button::A,
+            GamepadButtons::B             => Button::B,
+            GamepadButtons::X             => Button::X,
+            GamepadButtons::Y             => Button::Y,
+            GamepadButtons::Back          => Button::Back,
+            GamepadButtons::Guide         => Button::Guide,
+            GamepadButtons::Start         => Button::Start,
+            GamepadButtons::LeftStick     => Button::LeftStick,
+            GamepadButtons::RightStick    => Button::RightStick,
+            GamepadButtons::LeftShoulder  => Button::LeftShoulder,
+            GamepadButtons::RightShoulder => Button::RightShoulder,
+            GamepadButtons::DPadUp        => Button::DPadUp,
+            GamepadButtons::DPadDown      => Button::DPadDown,
+            GamepadButtons::DPadLeft      => Button::DPadLeft,
+            GamepadButtons::DPadRight     => Button::DPadRight,
+        }
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, AsRefStr, Display, EnumCount, EnumIter, EnumString)]
+pub enum GamepadAxes {
+    // Sticks (-1 left/up, 1 right/down)
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    // Triggers (0 released, 1 fully pressed)
+    TriggerLeft,
+    TriggerRight,
+}
+
+impl GamepadAxes {
+    /*
+     * to_sdl_axis()
+     *
+     * @Brief : Bind Game2D GamepadAxes to SDL Axis
+     */
+    pub(crate) fn to_sdl_axis(self) -> Axis {
+        match self {
+            GamepadAxes::LeftX        => Axis::LeftX,
+            GamepadAxes::LeftY        => Axis::LeftY,
+            GamepadAxes::RightX       => Axis::RightX,
+            GamepadAxes::RightY       => Axis::RightY,
+            GamepadAxes::TriggerLeft  => Axis::TriggerLeft,
+            GamepadAxes::TriggerRight => Axis::TriggerRight,
+        }
+    }
+}
+
+/*
+ * GamepadEvent : hot-plug of a gamepad during the frame
+ */
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum GamepadEvent {
+    Connected(PlayerId),
+    Disconnected(PlayerId),
+}
+
+/*================================================================
+ *                          G A M E P A D
+ *================================================================*/
+pub struct Gamepad {
+    id: GamepadId,
+    name: String,
+
+    // Buttons (edges are valid for the current frame)
+    buttons_down: Vec<GamepadButtons>,
+    buttons_just_pressed: Vec<GamepadButtons>,
+    buttons_just_released: Vec<GamepadButtons>,
+
+    axes: HashMap<GamepadAxes, AxisValue>,
+}
+
+#[allow(dead_code)]
+impl Gamepad {
+    fn new(id: GamepadId, name: &str) -> Gamepad {
+        Gamepad {
+            id,
+            name: name.to_string(),
+            buttons_down: Vec::new(),
+            buttons_just_pressed: Vec::new(),
+            buttons_just_released: Vec::new(),
+            axes: HashMap::new(),
+        }
+    }
+
+    pub fn get_id(&self) -> GamepadId {
+        self.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn next_frame(&mut self) {
+        self.buttons_just_pressed.clear();
+        self.buttons_just_released.clear();
+    }
+
+    fn add_button_down(&mut self, button: GamepadButtons) {
+        if !self.buttons_down.contains(&button) {
+            self.buttons_down.push(button);
+            self.buttons_just_pressed.push(button);
+        }
+    }
+
+    fn add_button_up(&mut self, button: GamepadButtons) {
+        if let Some(i) = self.buttons_down.iter().position(|b| *b == button) {
+            self.buttons_down.remove(i);
+            self.buttons_just_released.push(button);
+        }
+    }
+
+    /*
+     * is_down()
+     *
+     * @Brief : Check if a button is down
+     */
+    pub fn is_down(&self, button: &GamepadButtons) -> bool {
+        self.buttons_down.contains(button)
+    }
+
+    /*
+     * is_just_pressed()
+     *
+     * @Brief : Check if a button has been pressed during this frame
+     */
+    pub fn is_just_pressed(&self, button: &GamepadButtons) -> bool {
+        self.buttons_just_pressed.contains(button)
+    }
+
+    /*
+     * is_just_released()
+     *
+     * @Brief : Check if a button has been released during this frame
+     */
+    pub fn is_just_released(&self, button: &GamepadButtons) -> bool {
+        self.buttons_just_released.contains(button)
+    }
+
+    /*
+     * get_buttons_pressed()
+     *
+     * @Brief : Get all buttons pressed during this frame
+     */
+    pub fn get_buttons_pressed(&self) -> Vec<GamepadButtons> {
+        self.buttons_just_pressed.clone()
+    }
+
+    /*
+     * get_axis()
+     *
+     * @Brief : Value of a stick axis (-1..1) or a trigger (0..1)
+     */
+    pub fn get_axis(&self, axis: &GamepadAxes) -> AxisValue {
+        self.axes.get(axis).copied().unwrap_or(0.)
+    }
+
+    pub fn get_left_stick(&self) -> (AxisValue, AxisValue) {
+        (self.get_axis(&GamepadAxes::LeftX), self.get_axis(&GamepadAxes::LeftY))
+    }
+
+    pub fn get_right_stick(&self) -> (AxisValue, AxisValue) {
+        (self.get_axis(&GamepadAxes::RightX), self.get_axis(&GamepadAxes::RightY))
+    }
+
+    pub fn get_left_trigger(&self) -> AxisValue {
+        self.get_axis(&GamepadAxes::TriggerLeft)
+    }
+
+    pub fn get_right_trigger(&self) -> AxisValue {
+        self.get_axis(&GamepadAxes::TriggerRight)
+    }
+}
+
+/*
+ * Slot : a player's gamepad with its SDL controller (None when
+ *        the state is set by hand)
+ */
+struct Slot {
+    gamepad: Gamepad,
+    controller: Option<GameController>,
+}
+
+/*================================================================
+ *                         G A M E P A D S
+ *================================================================*/
+#[derive(Default)]
+pub struct Gamepads {
+    subsystem: Option<GameControllerSubsystem>,
+
+    // Index = player, the slot of a disconnected player stays empty
+    players: Vec<Option<Slot>>,
+
+    // SDL controllers opened, waiting for their GamepadConnected event
+    opened: HashMap<GamepadId, GameController>,
+
+    // Hot-plug events of the frame
+    events: Vec<GamepadEvent>,
+}
+
+#[allow(dead_code)]
+impl Gamepads {
+    /*
+     * new()
+     *
+     * @Brief : Gamepads reading the SDL controllers (None : state
+     *          set by hand only). The controllers already plugged
+     *          are connected by the events SDL sends at start.
+     */
+    pub fn new(subsystem: Option<GameControllerSubsystem>) -> Gamepads {
+        Gamepads { subsystem, ..Default::default() }
+    }
+
+    /*
+     * init()
+     *
+     * @Brief : Gamepads with their own SDL context (tests, tools)
+     */
+    pub fn init() -> Game2dResult<Gamepads> {
+        let subsystem = sdl2::init()
+            .and_then(|sdl| sdl.game_controller())
+            .map_err(Game2dError::Init)?;
+        Ok(Gamepads::new(Some(subsystem)))
+    }
+
+    //=======================================================================
+    //                               HOT-PLUG
+    //=======================================================================
+    /*
+     * open()
+     *
+     * @Brief : Open the SDL controller at the joystick index, the
+     *          gamepad is connected when the event returned is applied
+     *          (None : not a controller or already connected)
+     */
+    pub fn open(&mut self, joystick_index: u32) -> Game2dResult<Option<InputEvent>> {
+        let subsystem = match &self.subsystem {
+            Some(subsystem) => subsystem,
+            None => return Ok(Option::None),
+        };
+        if !subsystem.is_game_controller(joystick_index) {
+            return Ok(Option::None);
+        }
+        let controller = subsystem
+            .open(joystick_index)
+            .map_err(|e| Game2dError::Init(e.to_string()))?;
+
+        let id = controller.instance_id();
+        if self.get_player(id).is_some() {
+            return Ok(Option::None);
+        }
+
+        let name = controller.name();
+        self.opened.insert(id, controller);
+        Ok(Some(InputEvent::GamepadConnected { id, name }))
+    }
+
+    /*
+     * connect()
+     *
+     * @Brief : Connect a gamepad to the first free player, with its
+     *          SDL controller if opened (without : state set by hand,
+     *          playback...)
+     */
+    pub fn connect(&mut self, id: GamepadId, name: &str) -> PlayerId {
+        match self.get_player(id) {
+            Some(player) => player,
+            None => {
+                let controller = self.opened.remove(&id);
+                self.add_slot(Slot { gamepad: Gamepad::new(id, name), controller })
+            },
+        }
+    }
+
+    /*
+     * disconnect()
+     *
+     * @Brief : Remove a gamepad, its player slot stays free for the
+     *          next gamepad connected
+     */
+    pub fn disconnect(&mut self, id: GamepadId) {
+        if let Some(player) = self.get_player(id) {
+            self.players[player] = Option::None;
+            self.events.push(GamepadEvent::Disconnected(player));
+        }
+    }
+
+    fn add_slot(&mut self, slot: Slot) -> PlayerId {
+        let player = match self.players.iter().position(Option::is_none) {
+            Some(player) => {
+                self.players[player] = Some(slot);
+                player
+            },
+            None => {
+                self.players.push(Some(slot));
+                self.players.len() - 1
+            },
+        };
+        self.events.push(GamepadEvent::Connected(player));
+        player
+    }
+
+    //=======================================================================
+    //                                STATE
+    //=======================================================================
+    /*
+     * poll()
+     *
+     * @Brief : Read the SDL controllers, return the changes since the
+     *          last frame (applied by the game with the other events)
+     */
+    pub fn poll(&self) -> Vec<InputEvent> {
+        let mut changes = Vec::new();
+        if let Some(subsystem) = &self.subsystem {
+            subsystem.update();
+        }
+
+        for slot in self.players.iter().flatten() {
+            let (gamepad, controller) = match &slot.controller {
+                Some(controller) => (&slot.gamepad, controller),
+                None => continue,
+            };
+            let id = gamepad.id;
+            if !controller.attached() {
+                changes.push(InputEvent::GamepadDisconnected(id));
+                continue;
+            }
+            for button in GamepadButtons::iter() {
+                match (controller.button(button.to_sdl_button()), gamepad.is_down(&button)) {
+                    (true, false) => changes.push(InputEvent::GamepadButtonDown { id, button }),
+                    (false, true) => changes.push(InputEvent::GamepadButtonUp { id, button }),
+                    _ => {},
+                }
+            }
+            for axis in GamepadAxes::iter() {
+                let value = normalize_axis(controller.axis(axis.to_sdl_axis()));
+                if value != gamepad.get_axis(&axis) {
+                    changes.push(InputEvent::GamepadAxis { id, axis, value });
+                }
+            }
+        }
+        changes
+    }
+
+    /*
+     * next_frame()
+     *
+     * @Brief : Roll the state over to a new frame (called by the game
+     *          after each frame)
+     */
+    pub fn next_frame(&mut self) {
+        self.events.clear();
+        for slot in self.players.iter_mut().flatten() {
+            slot.gamepad.next_frame();
+        }
+    }
+
+    /*
+     * add_button_down()
+     *
+     * @Brief : Add a button down on the gamepad of a player
+     */
+    pub fn add_button_down(&mut self, player: PlayerId, button: Option<GamepadButtons>) {
+        if let (Some(gamepad), Some(button)) = (self.get_mut(player), button) {
+            gamepad.add_button_down(button);
+        }
+    }
+
+    /*
+     * add_button_up()
+     *
+     * @Brief : Add a button up on the gamepad of a player
+     */
+    pub fn add_button_up(&mut self, player: PlayerId, button: Option<GamepadButtons>) {
+        if let (Some(gamepad), Some(button)) = (self.get_mut(player), button) {
+            gamepad.add_button_up(button);
+        }
+    }
+
+    /*
+     * set_axis()
+     *
+     * @Brief : Set an axis of the gamepad of a player
+     */
+    pub fn set_axis(&mut self, player: PlayerId, axis: GamepadAxes, value: AxisValue) {
+        if let Some(gamepad) = self.get_mut(player) {
+            gamepad.axes.insert(axis, value.clamp(-1., 1.));
+        }
+    }
+
+    //=======================================================================
+    //                               QUERIES
+    //=======================================================================
+    /*
+     * get()
+     *
+     * @Brief : Gamepad of a player (None if not connected)
+     */
+    pub fn get(&self, player: PlayerId) -> Option<&Gamepad> {
+        self.players.get(player).and_then(Option::as_ref).map(|s| &s.gamepad)
+    }
+
+    fn get_mut(&mut self, player: PlayerId) -> Option<&mut Gamepad> {
+        self.players.get_mut(player).and_then(Option::as_mut).map(|s| &mut s.gamepad)
+    }
+
+    /*
+     * get_player()
+     *
+     * @Brief : Player of a gamepad
+     */
+    pub fn get_player(&self, id: GamepadId) -> Option<PlayerId> {
+        self.players
+            .iter()
+            .position(|s| matches!(s, Some(slot) if slot.gamepad.id == id))
+    }
+
+    /*
+     * get_players()
+     *
+     * @Brief : Players with a gamepad connected
+     */
+    pub fn get_players(&self) -> Vec<PlayerId> {
+        self.players
+            .iter()
+            .enumerate()
+            .filter_map(|(player, s)| s.as_ref().map(|_| player))
+            .collect()
+    }
+
+    pub fn is_connected(&self, player: PlayerId) -> bool {
+        self.get(player).is_some()
+    }
+
+    /*
+     * get_events()
+     *
+     * @Brief : Gamepads connected / disconnected during this frame
+     */
+    pub fn get_events(&self) -> &[GamepadEvent] {
+        &self.events
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Gamepad> {
+        self.players.iter().flatten().map(|s| &s.gamepad)
+    }
+
+    /*
+     * is_any_down()
+     *
+     * @Brief : Check if a button is down on one of the gamepads
+     */
+    pub fn is_any_down(&self, button: &GamepadButtons) -> bool {
+        self.iter().any(|g| g.is_down(button))
+    }
+
+    pub fn is_any_just_pressed(&self, button: &GamepadButtons) -> bool {
+        self.iter().any(|g| g.is_just_pressed(button))
+    }
+
+    pub fn is_any_just_released(&self, button: &GamepadButtons) -> bool {
+        self.iter().any(|g| g.is_just_released(button))
+    }
+
+    /*
+     * get_any_axis()
+     *
+     * @Brief : Value of an axis with the largest magnitude among the
+     *          gamepads
+     */
+    pub fn get_any_axis(&self, axis: &GamepadAxes) -> AxisValue {
+        self.iter()
+            .map(|g| g.get_axis(axis))
+            .fold(0., |a, v| if v.abs() > a.abs() { v } else { a })
+    }
+
+    //=======================================================================
+    //                           VIRTUAL GAMEPADS
+    //=======================================================================
+    /*
+     * attach_virtual()
+     *
+     * @Brief : Plug a virtual SDL controller (tests without hardware),
+     *          connected at once, driven with set_virtual_button() /
+     *          set_virtual_axis()
+     */
+    pub fn attach_virtual(&mut self) -> Game2dResult<PlayerId> {
+        if self.subsystem.is_none() {
+            return Err(Game2dError::Init(String::from("Gamepads created without SDL")));
+        }
+        let index = unsafe {
+            sdl2::sys::SDL_JoystickAttachVirtual(
+                sdl2::sys::SDL_JoystickType::SDL_JOYSTICK_TYPE_GAMECONTROLLER,
+                GamepadAxes::COUNT as i32,
+                GamepadButtons::COUNT as i32,
+                0,
+            )
+        };
+        if index < 0 {
+            return Err(Game2dError::Init(sdl2::get_error()));
+        }
+        match self.open(index as u32)? {
+            Some(InputEvent::GamepadConnected { id, name }) => Ok(self.connect(id, &name)),
+            _ => Err(Game2dError::Init(String::from("Virtual joystick isn't a game controller"))),
+        }
+    }
+
+    /*
+     * set_virtual_button()
+     *
+     * @Brief : Press / release a button of a virtual controller
+     *          (read by the next poll())
+     */
+    pub fn set_virtual_button(&self, player: PlayerId, button: GamepadButtons, down: bool) -> Game2dResult<()> {
+        let joystick = self.get_virtual_joystick(player)?;
+        let result = unsafe {
+            sdl2::sys::SDL_JoystickSetVirtualButton(joystick, button.to_sdl_button() as i32, down as u8)
+        };
+        if result < 0 { Err(Game2dError::Init(sdl2::get_error())) } else { Ok(()) }
+    }
+
+    /*
+     * set_virtual_axis()
+     *
+     * @Brief : Move an axis of a virtual controller (read by the next
+     *          poll())
+     */
+    pub fn set_virtual_axis(&self, player: PlayerId, axis: GamepadAxes, value: AxisValue) -> Game2dResult<()> {
+        let joystick = self.get_virtual_joystick(player)?;
+        let value = virtual_axis_value(axis, value);
+        let result = unsafe {
+            sdl2::sys::SDL_JoystickSetVirtualAxis(joystick, axis.to_sdl_axis() as i32, value)
+        };
+        if result < 0 { Err(Game2dError::Init(sdl2::get_error())) } else { Ok(()) }
+    }
+
+    fn get_virtual_joystick(&self, player: PlayerId) -> Game2dResult<*mut sdl2::sys::SDL_Joystick> {
+        let slot = self.players
+            .get(player)
+            .and_then(Option::as_ref)
+            .filter(|s| s.controller.is_some())
+            .ok_or_else(|| Game2dError::Init(format!("No SDL controller for player {}", player)))?;
+        let joystick = unsafe { sdl2::sys::SDL_JoystickFromInstanceID(slot.gamepad.id as i32) };
+        if joystick.is_null() {
+            return Err(Game2dError::Init(sdl2::get_error()));
+        }
+        Ok(joystick)
+    }
+}
+
+/*
+ * normalize_axis()
+ *
+ * @Brief : SDL axis value (-32768..32767) to -1..1
+ */
+fn normalize_axis(value: i16) -> AxisValue {
+    (value as AxisValue / i16::MAX as AxisValue).clamp(-1., 1.)
+}
+
+/*
+ * virtual_axis_value()
+ *
+ * @Brief : Value of an axis to raw value of a virtual joystick. Sticks :
+ *          -1..1 to -32767..32767, triggers : 0..1 to the full range
+ *          -32768..32767 (SDL maps it back to 0..32767)
+ */
+fn virtual_axis_value(axis: GamepadAxes, value: AxisValue) -> i16 {
+    match axis {
+        GamepadAxes::TriggerLeft | GamepadAxes::TriggerRight => {
+            (value.clamp(0., 1.) * u16::MAX as AxisValue + i16::MIN as AxisValue).round() as i16
+        },
+        _ => (value.clamp(-1., 1.) * i16::MAX as AxisValue).round() as i16,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn virtual_axis_values() {
+        assert_eq!(virtual_axis_value(GamepadAxes::LeftX, -1.), -32767);
+        assert_eq!(virtual_axis_value(GamepadAxes::LeftX, 0.), 0);
+        assert_eq!(virtual_axis_value(GamepadAxes::RightY, 1.), 32767);
+        assert_eq!(virtual_axis_value(GamepadAxes::RightY, 2.), 32767);
+
+        assert_eq!(virtual_axis_value(GamepadAxes::TriggerLeft, 0.), -32768);
+        assert_eq!(virtual_axis_value(GamepadAxes::TriggerRight, 1.), 32767);
+        assert_eq!(virtual_axis_value(GamepadAxes::TriggerRight, -1.), -32768);
+    }
+
+    #[test]
+    fn virtual_joystick_sticks_and_triggers() {
+        let sdl = sdl2::init().expect("SDL");
+        let mut gamepads = Gamepads::new(Some(sdl.game_controller().expect("game controller subsystem")));
+        let player = gamepads.attach_virtual().expect("virtual gamepad");
+
+        let moves = [
+            (GamepadAxes::LeftX, -1.),
+            (GamepadAxes::LeftY, 0.5),
+            (GamepadAxes::TriggerLeft, 1.),
+            (GamepadAxes::TriggerRight, 0.),
+        ];
+        for (axis, value) in moves {
+            gamepads.set_virtual_axis(player, axis, value).unwrap();
+        }
+        for event in gamepads.poll() {
+            if let InputEvent::GamepadAxis { axis, value, .. } = event {
+                gamepads.set_axis(player, axis, value);
+            }
+        }
+
+        let gamepad = gamepads.get(player).unwrap();
+        for (axis, value) in moves {
+            let read = gamepad.get_axis(&axis);
+            assert!((read - value).abs() < 0.001, "{} : {} read, {} expected", axis, read, value);
+        }
+    }
+}
@@ -0,0 +1,499 @@
+/*================================================================
+ *                       I N P U T   M A P
+ *
+ * Named actions ("jump") and axes ("move_x") bound to keys, mouse
+ * and gamepad buttons, rebindable at runtime and saved in a config
+ * file :
+ *
+ *   action jump = Key:Space, Mouse:Left, Gamepad:A
+ *   axis move_x 0.2 = Key:Left/Key:Right, Physical:A/Physical:D, Stick:LeftX
+ *
+ * Gamepad bindings match the gamepads of all the players.
+ *================================================================*/
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use strum::IntoEnumIterator;
+
+use crate::error::{Game2dError, Game2dResult};
+use crate::game::inputs::Inputs;
+use super::gamepad::{GamepadAxes, GamepadButtons};
+use super::keyboard::Keys;
+use super::mouse::MouseButtons;
+
+pub type AxisValue = f32;
+
+pub const INPUT_MAP_DEFAULT_DEAD_ZONE: AxisValue = 0.;
+
+/*
+ * InputBinding : one input that can trigger an action
+ */
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
+pub enum InputBinding {
+    // Key by symbol (layout dependent)
+    Key(Keys),
+    // Key by physical position (US layout names)
+    Physical(Keys),
+    Mouse(MouseButtons),
+    Gamepad(GamepadButtons),
+}
+
+impl InputBinding {
+    /*
+     * get_value()
+     *
+     * @Brief : 1 if the input is down, 0 otherwise
+     */
+    pub fn get_value(&self, inputs: &Inputs) -> AxisValue {
+        if self.is_down(inputs) { 1. } else { 0. }
+    }
+
+    pub fn is_down(&self, inputs: &Inputs) -> bool {
+        match self {
+            InputBinding::Key(key) => inputs.keyboard.is_down(key),
+            InputBinding::Physical(key) => inputs.keyboard.is_physical_down(key),
+            InputBinding::Mouse(button) => inputs.mouse.is_down(button),
+            InputBinding::Gamepad(button) => inputs.gamepads.is_any_down(button),
+        }
+    }
+
+    pub fn is_just_pressed(&self, inputs: &Inputs) -> bool {
+        match self {
+            InputBinding::Key(key) => inputs.keyboard.is_just_pressed(key),
+            InputBinding::Physical(key) => inputs.keyboard.is_physical_just_pressed(key),
+            InputBinding::Mouse(button) => inputs.mouse.is_just_pressed(button),
+            InputBinding::Gamepad(button) => inputs.gamepads.is_any_just_pressed(button),
+        }
+    }
+
+    pub fn is_just_released(&self, inputs: &Inputs) -> bool {
+        match self {
+            InputBinding::Key(key) => inputs.keyboard.is_just_released(key),
+            InputBinding::Physical(key) => inputs.keyboard.is_physical_just_released(key),
+            InputBinding::Mouse(button) => inputs.mouse.is_just_released(button),
+            InputBinding::Gamepad(button) => inputs.gamepads.is_any_just_released(button),
+        }
+    }
+}
+
+impl fmt::Display for InputBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputBinding::Key(key) => write!(f, "Key:{}", key),
+            InputBinding::Physical(key) => write!(f, "Physical:{}", key),
+            InputBinding::Mouse(button) => write!(f, "Mouse:{}", button),
+            InputBinding::Gamepad(button) => write!(f, "Gamepad:{}", button),
+        }
+    }
+}
+
+impl FromStr for InputBinding {
+    type Err = String;
+
+    fn from_str(binding: &str) -> Result<Self, Self::Err> {
+        let (kind, name) = binding
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| format!("Binding {} must be <Kind>:<Name>", binding))?;
+        let name = name.trim();
+        let unknown = |_| format!("Unknown input {} in binding {}", name, binding);
+
+        match kind.trim() {
+            "Key" => Ok(InputBinding::Key(Keys::from_str(name).map_err(unknown)?)),
+            "Physical" => Ok(InputBinding::Physical(Keys::from_str(name).map_err(unknown)?)),
+            "Mouse" => Ok(InputBinding::Mouse(MouseButtons::from_str(name).map_err(unknown)?)),
+            "Gamepad" => Ok(InputBinding::Gamepad(GamepadButtons::from_str(name).map_err(unknown)?)),
+            _ => Err(format!("Unknown kind {} in binding {}", kind, binding)),
+        }
+    }
+}
+
+/*
+ * AxisBinding : inputs giving a value between -1 and 1
+ */
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum AxisBinding {
+    // Two digital inputs (-1 / +1)
+    Digital { negative: InputBinding, positive: InputBinding },
+    // Gamepad stick or trigger
+    Analog(GamepadAxes),
+}
+
+impl AxisBinding {
+    pub fn get_value(&self, inputs: &Inputs) -> AxisValue {
+        match self {
+            AxisBinding::Digital { negative, positive } => positive.get_value(inputs) - negative.get_value(inputs),
+            AxisBinding::Analog(axis) => inputs.gamepads.get_any_axis(axis),
+        }
+    }
+}
+
+impl fmt::Display for AxisBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AxisBinding::Digital { negative, positive } => write!(f, "{}/{}", negative, positive),
+            AxisBinding::Analog(axis) => write!(f, "Stick:{}", axis),
+        }
+    }
+}
+
+impl FromStr for AxisBinding {
+    type Err = String;
+
+    fn from_str(binding: &str) -> Result<Self, Self::Err> {
+        if let Some(axis) = binding.trim().strip_prefix("Stick:") {
+            return GamepadAxes::from_str(axis.trim())
+                .map(AxisBinding::Analog)
+                .map_err(|_| format!("Unknown gamepad axis {} in binding {}", axis, binding));
+        }
+        let (negative, positive) = binding
+            .split_once('/')
+            .ok_or_else(|| format!("Axis binding {} must be <Negative>/<Positive>", binding))?;
+        Ok(AxisBinding::Digital {
+            negative: InputBinding::from_str(negative)?,
+            positive: InputBinding::from_str(positive)?,
+        })
+    }
+}
+
+/*
+ * Axis : bindings of an axis with its dead zone
+ */
+#[derive(PartialEq, Debug, Clone)]
+struct Axis {
+    bindings: Vec<AxisBinding>,
+    dead_zone: AxisValue,
+}
+
+/*================================================================
+ *                       I N P U T   M A P
+ *================================================================*/
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct InputMap {
+    actions: BTreeMap<String, Vec<InputBinding>>,
+    axes: BTreeMap<String, Axis>,
+}
+
+#[allow(dead_code)]
+impl InputMap {
+    pub fn new() -> InputMap {
+        InputMap::default()
+    }
+
+    //=======================================================================
+    //                               BINDINGS
+    //=======================================================================
+    /*
+     * bind()
+     *
+     * @Brief : Bind an input to an action
+     */
+    pub fn bind(&mut self, action: &str, binding: InputBinding) -> &mut Self {
+        let bindings = self.actions.entry(action.to_string()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /*
+     * unbind()
+     *
+     * @Brief : Remove an input from an action
+     */
+    pub fn unbind(&mut self, action: &str, binding: &InputBinding) -> &mut Self {
+        if let Some(bindings) = self.actions.get_mut(action) {
+            bindings.retain(|b| b != binding);
+        }
+        self
+    }
+
+    /*
+     * rebind()
+     *
+     * @Brief : Replace all the inputs of an action
+     */
+    pub fn rebind(&mut self, action: &str, binding: InputBinding) -> &mut Self {
+        self.actions.insert(action.to_string(), vec![binding]);
+        self
+    }
+
+    /*
+     * get_bindings()
+     *
+     * @Brief : Inputs bound to an action
+     */
+    pub fn get_bindings(&self, action: &str) -> &[InputBinding] {
+        self.actions.get(action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /*
+     * bind_axis()
+     *
+     * @Brief : Bind inputs to an axis
+     */
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) -> &mut Self {
+        let axis = self.axes.entry(axis.to_string()).or_insert_with(|| Axis {
+            bindings: Vec::new(),
+            dead_zone: INPUT_MAP_DEFAULT_DEAD_ZONE,
+        });
+        if !axis.bindings.contains(&binding) {
+            axis.bindings.push(binding);
+        }
+        self
+    }
+
+    /*
+     * unbind_axis()
+     *
+     * @Brief : Remove inputs from an axis
+     */
+    pub fn unbind_axis(&mut self, axis: &str, binding: &AxisBinding) -> &mut Self {
+        if let Some(axis) = self.axes.get_mut(axis) {
+            axis.bindings.retain(|b| b != binding);
+        }
+        self
+    }
+
+    /*
+     * set_dead_zone()
+     *
+     * @Brief : Values of an axis under the dead zone are 0
+     */
+    pub fn set_dead_zone(&mut self, axis: &str, dead_zone: AxisValue) -> &mut Self {
+        if let Some(axis) = self.axes.get_mut(axis) {
+            axis.dead_zone = dead_zone.clamp(0., 0.99);
+        }
+        self
+    }
+
+    /*
+     * get_axis_bindings()
+     *
+     * @Brief : Inputs bound to an axis
+     */
+    pub fn get_axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map(|a| a.bindings.as_slice()).unwrap_or(&[])
+    }
+
+    /*
+     * capture()
+     *
+     * @Brief : First input pressed during this frame (for a rebind menu)
+     */
+    pub fn capture(inputs: &Inputs) -> Option<InputBinding> {
+        if let Some(key) = Keys::iter().find(|k| inputs.keyboard.is_just_pressed(k)) {
+            return Some(InputBinding::Key(key));
+        }
+        if let Some(button) = MouseButtons::iter().find(|b| inputs.mouse.is_just_pressed(b)) {
+            return Some(InputBinding::Mouse(button));
+        }
+        GamepadButtons::iter()
+            .find(|b| inputs.gamepads.is_any_just_pressed(b))
+            .map(InputBinding::Gamepad)
+    }
+
+    //=======================================================================
+    //                               QUERIES
+    //=======================================================================
+    /*
+     * is_down()
+     *
+     * @Brief : One of the inputs of the action is down
+     */
+    pub fn is_down(&self, inputs: &Inputs, action: &str) -> bool {
+        self.get_bindings(action).iter().any(|b| b.is_down(inputs))
+    }
+
+    /*
+     * is_just_pressed()
+     *
+     * @Brief : The action has been triggered during this frame
+     */
+    pub fn is_just_pressed(&self, inputs: &Inputs, action: &str) -> bool {
+        let bindings = self.get_bindings(action);
+        bindings.iter().any(|b| b.is_just_pressed(inputs))
+            && !bindings.iter().any(|b| b.is_down(inputs) && !b.is_just_pressed(inputs))
+    }
+
+    /*
+     * is_just_released()
+     *
+     * @Brief : The last input of the action has been released during this frame
+     */
+    pub fn is_just_released(&self, inputs: &Inputs, action: &str) -> bool {
+        let bindings = self.get_bindings(action);
+        bindings.iter().any(|b| b.is_just_released(inputs))
+            && !bindings.iter().any(|b| b.is_down(inputs))
+    }
+
+    /*
+     * get_axis()
+     *
+     * @Brief : Value of an axis between -1 and 1, dead zone applied
+     */
+    pub fn get_axis(&self, inputs: &Inputs, axis: &str) -> AxisValue {
+        match self.axes.get(axis) {
+            Some(axis) => {
+                let value: AxisValue = axis.bindings
+                    .iter()
+                    .map(|b| b.get_value(inputs))
+                    .sum::<AxisValue>()
+                    .clamp(-1., 1.);
+                apply_dead_zone(value, axis.dead_zone)
+            },
+            None => 0.,
+        }
+    }
+
+    //=======================================================================
+    //                                CONFIG
+    //=======================================================================
+    /*
+     * load()
+     *
+     * @Brief : Load an input map from a config file
+     */
+    pub fn load(filename: &str) -> Game2dResult<InputMap> {
+        let config = std::fs::read_to_string(filename)
+            .map_err(|e| Game2dError::AssetIo { path: filename.to_string(), message: e.to_string() })?;
+        InputMap::from_str(&config)
+            .map_err(|message| Game2dError::Decode { path: filename.to_string(), message })
+    }
+
+    /*
+     * save()
+     *
+     * @Brief : Save the input map in a config file
+     */
+    pub fn save(&self, filename: &str) -> Game2dResult<()> {
+        std::fs::write(filename, self.to_string())
+            .map_err(|e| Game2dError::AssetIo { path: filename.to_string(), message: e.to_string() })
+    }
+}
+
+/*
+ * apply_dead_zone()
+ *
+ * @Brief : 0 under the dead zone, rescaled between the dead zone and 1
+ */
+pub fn apply_dead_zone(value: AxisValue, dead_zone: AxisValue) -> AxisValue {
+    if value.abs() <= dead_zone {
+        0.
+    } else {
+        value.signum() * (value.abs() - dead_zone) / (1. - dead_zone)
+    }
+}
+
+impl fmt::Display for InputMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (action, bindings) in self.actions.iter() {
+            let bindings: Vec<String> = bindings.iter().map(|b| b.to_string()).collect();
+            writeln!(f, "action {} = {}", action, bindings.join(", "))?;
+        }
+        for (name, axis) in self.axes.iter() {
+            let bindings: Vec<String> = axis.bindings.iter().map(|b| b.to_string()).collect();
+            writeln!(f, "axis {} {} = {}", name, axis.dead_zone, bindings.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for InputMap {
+    type Err = String;
+
+    fn from_str(config: &str) -> Result<Self, Self::Err> {
+        let mut map = InputMap::new();
+
+        for (number, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |e: String| format!("Line {} : {}", number + 1, e);
+
+            let (head, bindings) = line
+                .split_once('=')
+                .ok_or_else(|| error(String::from("'=' expected")))?;
+            let head: Vec<&str> = head.split_whitespace().collect();
+            let bindings = bindings.split(',').map(str::trim).filter(|b| !b.is_empty());
+
+            match head.as_slice() {
+                ["action", name] => {
+                    map.actions.entry(name.to_string()).or_default();
+                    for binding in bindings {
+                        map.bind(name, InputBinding::from_str(binding).map_err(error)?);
+                    }
+                },
+                ["axis", name, dead_zone @ ..] => {
+                    let dead_zone = match dead_zone {
+                        [] => INPUT_MAP_DEFAULT_DEAD_ZONE,
+                        [value] => value.parse().map_err(|_| error(format!("Bad dead zone {}", value)))?,
+                        _ => return Err(error(String::from("axis <name> [dead zone] expected"))),
+                    };
+                    map.axes.insert(name.to_string(), Axis { bindings: Vec::new(), dead_zone });
+                    for binding in bindings {
+                        map.bind_axis(name, AxisBinding::from_str(binding).map_err(error)?);
+                    }
+                    map.set_dead_zone(name, dead_zone);
+                },
+                _ => return Err(error(String::from("'action <name>' or 'axis <name> [dead zone]' expected"))),
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "\
+action fire = Mouse:Left, Gamepad:X
+action jump = Key:Space, Physical:W
+axis move_x 0.25 = Key:Left/Key:Right, Stick:LeftX
+";
+
+    #[test]
+    fn parse_config() {
+        let map = InputMap::from_str(CONFIG).unwrap();
+        assert_eq!(map.get_bindings("jump"), [InputBinding::Key(Keys::Space), InputBinding::Physical(Keys::W)]);
+        assert_eq!(map.get_bindings("fire"), [InputBinding::Mouse(MouseButtons::Left), InputBinding::Gamepad(GamepadButtons::X)]);
+        assert_eq!(map.get_axis_bindings("move_x"), [
+            AxisBinding::Digital { negative: InputBinding::Key(Keys::Left), positive: InputBinding::Key(Keys::Right) },
+            AxisBinding::Analog(GamepadAxes::LeftX),
+        ]);
+        assert_eq!(map.axes["move_x"].dead_zone, 0.25);
+    }
+
+    #[test]
+    fn config_round_trip() {
+        let map = InputMap::from_str(CONFIG).unwrap();
+        assert_eq!(map.to_string(), CONFIG);
+        assert_eq!(InputMap::from_str(&map.to_string()).unwrap(), map);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let map = InputMap::from_str(CONFIG).unwrap();
+        let filename = std::env::temp_dir().join(format!("game2d_input_map_{}.cfg", std::process::id()));
+        let filename = filename.to_string_lossy();
+
+        map.save(&filename).unwrap();
+        let loaded = InputMap::load(&filename);
+        let _ = std::fs::remove_file(filename.as_ref());
+        assert_eq!(loaded.unwrap(), map);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(InputMap::from_str("action jump Key:Space").is_err());
+        assert!(InputMap::from_str("action jump = Key:Nope").is_err());
+        assert!(InputMap::from_str("axis move_x abc = Stick:LeftX").is_err());
+        assert!(InputMap::from_str("button jump = Key:Space").is_err());
+        // Comments and blank lines
+        assert_eq!(InputMap::from_str("# bindings\n\n").unwrap(), InputMap::new());
+    }
+}
@@ -1,30 +1,326 @@
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fmt;
+use std::str::FromStr;
 
-use sdl2::keyboard::Keycode;
+use sdl2::keyboard::{Keycode, Mod, Scancode};
+use strum_macros::{AsRefStr, Display, EnumCount, EnumIter, EnumString};
 
-#[derive(Eq, Hash, PartialEq, Debug)]
-pub enum Keys {
-    Space,
-    Up,
-    Down,
-    Left,
-    Right,
+use crate::game::common::DeltaTime;
+
+/*
+ * keys!
+ *
+ * @Brief : Declare the Keys enum and its binding with SDL keycodes
+ *          (layout dependent) and scancodes (physical position on
+ *          a US keyboard)
+ */
+macro_rules! keys {
+    ($($key:ident => $keycode:ident / $scancode:ident),* $(,)?) => {
+        #[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, AsRefStr, Display, EnumCount, EnumIter, EnumString)]
+        #[strum(ascii_case_insensitive)]
+        pub enum Keys {
+            $($key,)*
+        }
+
+        impl Keys {
+            /*
+             * from_sdl_keycode()
+             *
+             * @Brief : Bind SDL KeyCode to Game2D Key
+             */
+            pub(crate) fn from_sdl_keycode(keycode: Keycode) -> Option<Keys> {
+                match keycode {
+                    $(Keycode::$keycode => Some(Keys::$key),)*
+                    _ => Option::None,
+                }
+            }
+
+            /*
+             * from_sdl_scancode()
+             *
+             * @Brief : Bind SDL Scancode to Game2D Key (US layout position)
+             */
+            pub(crate) fn from_sdl_scancode(scancode: Scancode) -> Option<Keys> {
+                match scancode {
+                    $(Scancode::$scancode => Some(Keys::$key),)*
+                    _ => Option::None,
+                }
+            }
+        }
+    };
 }
 
-pub struct Keyboard {
-    keys_released: HashMap<u32, bool>,
-    keys_up: HashMap<u32, bool>,
-    keys_down: HashMap<u32, bool>,
+keys! {
+    Space        => Space        / Space,
+    Up           => Up           / Up,
+    Down         => Down         / Down,
+    Left         => Left         / Left,
+    Right        => Right        / Right,
+    A            => A            / A,
+    B            => B            / B,
+    C            => C            / C,
+    D            => D            / D,
+    E            => E            / E,
+    F            => F            / F,
+    G            => G            / G,
+    H            => H            / H,
+    I            => I            / I,
+    J            => J            / J,
+    K            => K            / K,
+    L            => L            / L,
+    M            => M            / M,
+    N            => N            / N,
+    O            => O            / O,
+    P            => P            / P,
+    Q            => Q            / Q,
+    R            => R            / R,
+    S            => S            / S,
+    T            => T            / T,
+    U            => U            / U,
+    V            => V            / V,
+    W            => W            / W,
+    X            => X            / X,
+    Y            => Y            / Y,
+    Z            => Z            / Z,
+    Num0         => Num0         / Num0,
+    Num1         => Num1         / Num1,
+    Num2         => Num2         / Num2,
+    Num3         => Num3         / Num3,
+    Num4         => Num4         / Num4,
+    Num5         => Num5         / Num5,
+    Num6         => Num6         / Num6,
+    Num7         => Num7         / Num7,
+    Num8         => Num8         / Num8,
+    Num9         => Num9         / Num9,
+    F1           => F1           / F1,
+    F2           => F2           / F2,
+    F3           => F3           / F3,
+    F4           => F4           / F4,
+    F5           => F5           / F5,
+    F6           => F6           / F6,
+    F7           => F7           / F7,
+    F8           => F8           / F8,
+    F9           => F9           / F9,
+    F10          => F10          / F10,
+    F11          => F11          / F11,
+    F12          => F12          / F12,
+    Escape       => Escape       / Escape,
+    Enter        => Return       / Return,
+    Tab          => Tab          / Tab,
+    Backspace    => Backspace    / Backspace,
+    Delete       => Delete       / Delete,
+    Insert       => Insert       / Insert,
+    Home         => Home         / Home,
+    End          => End          / End,
+    PageUp       => PageUp       / PageUp,
+    PageDown     => PageDown     / PageDown,
+    CapsLock     => CapsLock     / CapsLock,
+    PrintScreen  => PrintScreen  / PrintScreen,
+    ScrollLock   => ScrollLock   / ScrollLock,
+    Pause        => Pause        / Pause,
+    Menu         => Application  / Application,
+    LShift       => LShift       / LShift,
+    RShift       => RShift       / RShift,
+    LCtrl        => LCtrl        / LCtrl,
+    RCtrl        => RCtrl        / RCtrl,
+    LAlt         => LAlt         / LAlt,
+    RAlt         => RAlt         / RAlt,
+    LGui         => LGui         / LGui,
+    RGui         => RGui         / RGui,
+    Kp0          => Kp0          / Kp0,
+    Kp1          => Kp1          / Kp1,
+    Kp2          => Kp2          / Kp2,
+    Kp3          => Kp3          / Kp3,
+    Kp4          => Kp4          / Kp4,
+    Kp5          => Kp5          / Kp5,
+    Kp6          => Kp6          / Kp6,
+    Kp7          => Kp7          / Kp7,
+    Kp8          => Kp8          / Kp8,
+    Kp9          => Kp9          / Kp9,
+    KpPlus       => KpPlus       / KpPlus,
+    KpMinus      => KpMinus      / KpMinus,
+    KpMultiply   => KpMultiply   / KpMultiply,
+    KpDivide     => KpDivide     / KpDivide,
+    KpEnter      => KpEnter      / KpEnter,
+    KpPeriod     => KpPeriod     / KpPeriod,
+    NumLock      => NumLockClear / NumLockClear,
+    Comma        => Comma        / Comma,
+    Period       => Period       / Period,
+    Slash        => Slash        / Slash,
+    Semicolon    => Semicolon    / Semicolon,
+    Quote        => Quote        / Apostrophe,
+    LeftBracket  => LeftBracket  / LeftBracket,
+    RightBracket => RightBracket / RightBracket,
+    Backslash    => Backslash    / Backslash,
+    Minus        => Minus        / Minus,
+    Equals       => Equals       / Equals,
+    Backquote    => Backquote    / Grave,
 }
 
-impl Default for Keyboard {
-    fn default() -> Self {
-        Keyboard { 
-            keys_released: HashMap::new(),
-            keys_up: HashMap::new(),
-            keys_down: HashMap::new(), 
+/*
+ * Modifiers : state of the modifier keys
+ */
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub gui: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers { shift: false, ctrl: false, alt: false, gui: false };
+
+    pub(crate) fn from_sdl_mod(keymod: Mod) -> Modifiers {
+        Modifiers {
+            shift: keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+            ctrl: keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+            alt: keymod.intersects(Mod::LALTMOD | Mod::RALTMOD),
+            gui: keymod.intersects(Mod::LGUIMOD | Mod::RGUIMOD),
+        }
+    }
+}
+
+/*
+ * KeyChord : a key with modifiers (Ctrl+S, Ctrl+Shift+Z...)
+ */
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
+pub struct KeyChord {
+    pub modifiers: Modifiers,
+    pub key: Keys,
+}
+
+impl KeyChord {
+    pub fn new(key: Keys) -> KeyChord {
+        KeyChord { modifiers: Modifiers::NONE, key }
+    }
+
+    pub fn shift(mut self) -> KeyChord {
+        self.modifiers.shift = true;
+        self
+    }
+
+    pub fn ctrl(mut self) -> KeyChord {
+        self.modifiers.ctrl = true;
+        self
+    }
+
+    pub fn alt(mut self) -> KeyChord {
+        self.modifiers.alt = true;
+        self
+    }
+
+    pub fn gui(mut self) -> KeyChord {
+        self.modifiers.gui = true;
+        self
+    }
+}
+
+/*
+ * "Ctrl+S", "Ctrl+Shift+Z", "Alt+Enter"... (case insensitive)
+ */
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(chord: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = Modifiers::NONE;
+        let mut key = Option::None;
+
+        for part in chord.split('+').map(str::trim) {
+            match part.to_lowercase().as_str() {
+                "shift" => modifiers.shift = true,
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" => modifiers.alt = true,
+                "gui" | "cmd" | "super" => modifiers.gui = true,
+                _ => {
+                    if key.is_some() {
+                        return Err(format!("Chord {} has more than one key", chord));
+                    }
+                    key = Some(Keys::from_str(part).map_err(|_| format!("Unknown key {} in chord {}", part, chord))?);
+                },
+            }
+        }
+
+        match key {
+            Some(key) => Ok(KeyChord { modifiers, key }),
+            None => Err(format!("Chord {} has no key", chord)),
+        }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.ctrl { write!(f, "Ctrl+")?; }
+        if self.modifiers.shift { write!(f, "Shift+")?; }
+        if self.modifiers.alt { write!(f, "Alt+")?; }
+        if self.modifiers.gui { write!(f, "Gui+")?; }
+        write!(f, "{}", self.key)
+    }
+}
+
+/*
+ * KeysState : keys down and edges of the frame
+ */
+#[derive(Default)]
+struct KeysState {
+    // Keys held, with the time they have been held
+    down: HashMap<Keys, DeltaTime>,
+    // Edges of the current frame (in the order of the events)
+    just_pressed: Vec<Keys>,
+    just_released: Vec<Keys>,
+}
+
+impl KeysState {
+    fn reset(&mut self) {
+        self.down.clear();
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+
+    fn next_frame(&mut self, dt: DeltaTime) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+        for held in self.down.values_mut() {
+            *held += dt;
+        }
+    }
+
+    fn add_down(&mut self, key: Keys) {
+        if let Entry::Vacant(entry) = self.down.entry(key) {
+            entry.insert(0.);
+            if !self.just_pressed.contains(&key) {
+                self.just_pressed.push(key);
+            }
+        }
+    }
+
+    fn add_up(&mut self, key: Keys) {
+        if self.down.remove(&key).is_some() && !self.just_released.contains(&key) {
+            self.just_released.push(key);
         }
     }
+
+    fn is_down(&self, key: &Keys) -> bool {
+        self.down.contains_key(key)
+    }
+
+    fn is_just_pressed(&self, key: &Keys) -> bool {
+        self.just_pressed.contains(key)
+    }
+
+    fn is_just_released(&self, key: &Keys) -> bool {
+        self.just_released.contains(key)
+    }
+}
+
+#[derive(Default)]
+pub struct Keyboard {
+    // Keys by symbol (depend on the layout)
+    keys: KeysState,
+    // Keys by physical position (US layout names)
+    scancodes: KeysState,
+    modifiers: Modifiers,
 }
 
 impl Keyboard {
@@ -34,54 +330,82 @@ impl Keyboard {
      * @Brief : Reset Keyboard state
      */
     pub fn reset(&mut self) {
-        self.keys_up.clear();
-        self.keys_down.clear();
-        self.keys_released.clear();
+        self.keys.reset();
+        self.scancodes.reset();
+        self.modifiers = Modifiers::NONE;
+    }
+
+    /*
+     * next_frame()
+     * 
+     * @Brief : Roll the state over to a new frame (called by the game
+     *          after each frame) : edges are forgotten, held keys get older
+     */
+    pub fn next_frame(&mut self, dt: DeltaTime) {
+        self.keys.next_frame(dt);
+        self.scancodes.next_frame(dt);
     }
 
     /*
      * add_key_down()
      * 
-     * @Brief : Add a new key down
+     * @Brief : Add a new key down (repeats are ignored)
      */
     pub fn add_key_down(&mut self, key: Option<Keys>) {
-
         if let Some(key) = key {
-            let x = key as u32;
-
-            self.keys_down.insert(x, true);
-            if self.keys_released.contains_key(&x) == false {
-                self.keys_released.insert(x, true);
-            }
+            self.keys.add_down(key);
         }
     }
 
     /*
      * add_key_up()
      * 
-     * @Brief : Add a new key UP
+     * @Brief : Add a new key UP (ignored if the key isn't down)
      */
     pub fn add_key_up(&mut self, key: Option<Keys>) {
-
         if let Some(key) = key {
-            let x = key as u32;
+            self.keys.add_up(key);
+        }
+    }
 
-            self.keys_up.insert(x, true);
-            self.keys_released.remove(&x).unwrap();
+    /*
+     * add_scancode_down()
+     * 
+     * @Brief : Add a new physical key down
+     */
+    pub fn add_scancode_down(&mut self, key: Option<Keys>) {
+        if let Some(key) = key {
+            self.scancodes.add_down(key);
+        }
+    }
 
-            // A Key can't be up and down in same time
-            self.keys_down.remove_entry(&x);
+    /*
+     * add_scancode_up()
+     * 
+     * @Brief : Add a new physical key UP
+     */
+    pub fn add_scancode_up(&mut self, key: Option<Keys>) {
+        if let Some(key) = key {
+            self.scancodes.add_up(key);
         }
     }
 
+    /*
+     * set_modifiers()
+     * 
+     * @Brief : Set the state of the modifier keys
+     */
+    pub fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+    }
+
     /*
      * is_down()
      * 
      * @Brief : Check if a key is down
      */
     pub fn is_down(&self, key: &Keys) -> bool {
-        let x: u32 = *key as u32;
-        self.keys_down.contains_key(&x)
+        self.keys.is_down(key)
     }
 
     /*
@@ -90,36 +414,123 @@ impl Keyboard {
      * @Brief : Check if a key is up
      */
     pub fn is_up(&self, key: &Keys) -> bool {
-        let x: u32 = *key as u32;
-        self.keys_up.contains_key(&x)
+        !self.is_down(key)
+    }
+
+    /*
+     * is_just_pressed()
+     * 
+     * @Brief : Check if a key has been pressed during this frame
+     */
+    pub fn is_just_pressed(&self, key: &Keys) -> bool {
+        self.keys.is_just_pressed(key)
+    }
+
+    /*
+     * is_just_released()
+     * 
+     * @Brief : Check if a key has been released during this frame
+     */
+    pub fn is_just_released(&self, key: &Keys) -> bool {
+        self.keys.is_just_released(key)
+    }
+
+    /*
+     * is_held()
+     * 
+     * @Brief : Check if a key is down since a previous frame
+     */
+    pub fn is_held(&self, key: &Keys) -> bool {
+        self.is_down(key) && !self.is_just_pressed(key)
+    }
+
+    /*
+     * get_held_duration()
+     * 
+     * @Brief : Time since the key is down (None if the key is up)
+     */
+    pub fn get_held_duration(&self, key: &Keys) -> Option<DeltaTime> {
+        self.keys.down.get(key).copied()
     }
 
     /*
      * get_keys_pressed()
      * 
-     * @Brief : Get all keys pressed
-     */
-    pub fn get_keys_pressed(&mut self) -> Vec<Keys> {
-        let mut vec = Vec::new();
-
-        for (k, v) in self.keys_released.iter_mut() {
-            if *v {
-                let key = match *k {
-                    0 => Some(Keys::Space),
-                    1 => Some(Keys::Up),
-                    2 => Some(Keys::Down),
-                    3 => Some(Keys::Left),
-                    4 => Some(Keys::Right),
-                    _ => Some(Keys::Down),
-                };
-                if let Some(k) = key {   
-                    vec.push(k);
-                }
-                *v = false;
-            }
-        }
+     * @Brief : Get all keys pressed during this frame
+     */
+    pub fn get_keys_pressed(&self) -> Vec<Keys> {
+        self.keys.just_pressed.clone()
+    }
+
+    /*
+     * get_keys_released()
+     * 
+     * @Brief : Get all keys released during this frame
+     */
+    pub fn get_keys_released(&self) -> Vec<Keys> {
+        self.keys.just_released.clone()
+    }
+
+    /*
+     * is_physical_down() / is_physical_just_pressed() / is_physical_just_released()
+     * 
+     * @Brief : Same as is_down()... with the physical position of the key
+     *          (named on a US layout) : Keys::W is the W of QWERTY and
+     *          the Z of AZERTY
+     */
+    pub fn is_physical_down(&self, key: &Keys) -> bool {
+        self.scancodes.is_down(key)
+    }
 
-        vec
+    pub fn is_physical_just_pressed(&self, key: &Keys) -> bool {
+        self.scancodes.is_just_pressed(key)
+    }
+
+    pub fn is_physical_just_released(&self, key: &Keys) -> bool {
+        self.scancodes.is_just_released(key)
+    }
+
+    /*
+     * get_modifiers()
+     * 
+     * @Brief : State of the modifier keys
+     */
+    pub fn get_modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    pub fn is_shift_down(&self) -> bool {
+        self.modifiers.shift
+    }
+
+    pub fn is_ctrl_down(&self) -> bool {
+        self.modifiers.ctrl
+    }
+
+    pub fn is_alt_down(&self) -> bool {
+        self.modifiers.alt
+    }
+
+    pub fn is_gui_down(&self) -> bool {
+        self.modifiers.gui
+    }
+
+    /*
+     * is_chord_down()
+     * 
+     * @Brief : Check if the key of a chord is down with exactly its modifiers
+     */
+    pub fn is_chord_down(&self, chord: &KeyChord) -> bool {
+        self.modifiers == chord.modifiers && self.is_down(&chord.key)
+    }
+
+    /*
+     * is_chord_just_pressed()
+     * 
+     * @Brief : Check if a chord has been pressed during this frame
+     */
+    pub fn is_chord_just_pressed(&self, chord: &KeyChord) -> bool {
+        self.modifiers == chord.modifiers && self.is_just_pressed(&chord.key)
     }
 
     /*
@@ -128,12 +539,184 @@ impl Keyboard {
      * @Brief : Bind SDL KeyCode to Game2D Key
      */
     pub(crate) fn _sdl_keycode_to_key(keycode: Keycode) -> Option<Keys> {
-        match keycode {
-            Keycode::Left   => { Some(Keys::Left) },
-            Keycode::Right  => { Some(Keys::Right) },
-            Keycode::Up     => { Some(Keys::Up) },
-            Keycode::Down   => { Some(Keys::Down)},
-            _               => Option::None,
-        }
+        Keys::from_sdl_keycode(keycode)
+    }
+
+    /*
+     * _sdl_scancode_to_key() 
+     * 
+     * @Brief : Bind SDL Scancode to Game2D Key
+     */
+    pub(crate) fn _sdl_scancode_to_key(scancode: Scancode) -> Option<Keys> {
+        Keys::from_sdl_scancode(scancode)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdl2::event::Event;
+
+    use crate::game::inputs::Inputs;
+    use crate::inputs::event::InputEvent;
+
+    /*
+     * SDL key event of a key typed on a layout
+     */
+    fn sdl_key(down: bool, keycode: Keycode, scancode: Scancode) -> InputEvent {
+        let (timestamp, window_id, keymod, repeat) = (0, 0, Mod::NOMOD, false);
+        let event = match down {
+            true => Event::KeyDown { timestamp, window_id, keycode: Some(keycode), scancode: Some(scancode), keymod, repeat },
+            false => Event::KeyUp { timestamp, window_id, keycode: Some(keycode), scancode: Some(scancode), keymod, repeat },
+        };
+        InputEvent::from_sdl_event(&event).unwrap()
+    }
+
+    fn chord(chord: &str) -> KeyChord {
+        chord.parse().unwrap()
+    }
+
+    fn keyboard_with(modifiers: Modifiers, key: Keys) -> Keyboard {
+        let mut keyboard = Keyboard::default();
+        keyboard.set_modifiers(modifiers);
+        keyboard.add_key_down(Some(key));
+        keyboard
+    }
+
+    #[test]
+    fn chord_parsing_ignores_case() {
+        let ctrl_s = KeyChord::new(Keys::S).ctrl();
+        assert_eq!(chord("Ctrl+S"), ctrl_s);
+        assert_eq!(chord("Ctrl+s"), ctrl_s);
+        assert_eq!(chord("ctrl+S"), ctrl_s);
+        assert_eq!(chord(" CONTROL + s "), ctrl_s);
+        assert_eq!(chord("alt+enter"), KeyChord::new(Keys::Enter).alt());
+        assert_eq!(chord("Ctrl+Shift+z"), KeyChord::new(Keys::Z).ctrl().shift());
+        assert_eq!(chord("space"), KeyChord::new(Keys::Space));
+
+        // Written back with the names of the keys
+        assert_eq!(chord("shift+ctrl+f1").to_string(), "Ctrl+Shift+F1");
+    }
+
+    #[test]
+    fn chord_parsing_errors() {
+        assert!("Ctrl+".parse::<KeyChord>().is_err());
+        assert!("Ctrl+Shift".parse::<KeyChord>().is_err());
+        assert!("Ctrl+S+D".parse::<KeyChord>().is_err());
+        assert!("Ctrl+NotAKey".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn chord_needs_the_exact_modifiers() {
+        let ctrl_s = chord("Ctrl+S");
+        let ctrl = Modifiers { ctrl: true, ..Modifiers::NONE };
+        let ctrl_shift = Modifiers { ctrl: true, shift: true, ..Modifiers::NONE };
+
+        let keyboard = keyboard_with(ctrl, Keys::S);
+        assert!(keyboard.is_chord_down(&ctrl_s));
+        assert!(keyboard.is_chord_just_pressed(&ctrl_s));
+
+        // An other modifier held : Ctrl+Shift+S isn't Ctrl+S
+        let keyboard = keyboard_with(ctrl_shift, Keys::S);
+        assert!(!keyboard.is_chord_down(&ctrl_s));
+        assert!(keyboard.is_chord_down(&chord("Ctrl+Shift+S")));
+
+        // Modifier missing, or an other key
+        assert!(!keyboard_with(Modifiers::NONE, Keys::S).is_chord_down(&ctrl_s));
+        assert!(!keyboard_with(ctrl, Keys::D).is_chord_down(&ctrl_s));
+
+        // Held from a previous frame : down, not just pressed
+        let mut keyboard = keyboard_with(ctrl, Keys::S);
+        keyboard.next_frame(0.1);
+        assert!(keyboard.is_chord_down(&ctrl_s));
+        assert!(!keyboard.is_chord_just_pressed(&ctrl_s));
+    }
+
+    #[test]
+    fn keycodes_and_scancodes_resolved_apart() {
+        let mut inputs = Inputs::default();
+
+        // AZERTY : the key at the place of the QWERTY W types a Z
+        inputs.apply(&sdl_key(true, Keycode::Z, Scancode::W));
+        let keyboard = &inputs.keyboard;
+        assert!(keyboard.is_down(&Keys::Z) && keyboard.is_just_pressed(&Keys::Z));
+        assert!(!keyboard.is_down(&Keys::W) && !keyboard.is_just_pressed(&Keys::W));
+        assert!(keyboard.is_physical_down(&Keys::W) && keyboard.is_physical_just_pressed(&Keys::W));
+        assert!(!keyboard.is_physical_down(&Keys::Z));
+        assert_eq!(keyboard.get_keys_pressed(), [Keys::Z]);
+
+        // And the key at the place of the QWERTY Z types a W
+        inputs.apply(&sdl_key(true, Keycode::W, Scancode::Z));
+        assert!(inputs.keyboard.is_down(&Keys::W) && inputs.keyboard.is_physical_down(&Keys::Z));
+
+        inputs.next_frame(0.1);
+        inputs.apply(&sdl_key(false, Keycode::Z, Scancode::W));
+        let keyboard = &inputs.keyboard;
+        assert!(keyboard.is_just_released(&Keys::Z) && keyboard.is_physical_just_released(&Keys::W));
+        assert!(!keyboard.is_just_released(&Keys::W) && !keyboard.is_physical_just_released(&Keys::Z));
+        assert!(keyboard.is_down(&Keys::W) && keyboard.is_physical_down(&Keys::Z));
+        assert!(keyboard.is_up(&Keys::Z) && !keyboard.is_physical_down(&Keys::W));
+    }
+
+    #[test]
+    fn sdl_keys_bound() {
+        assert_eq!(Keys::from_sdl_keycode(Keycode::Return), Some(Keys::Enter));
+        assert_eq!(Keys::from_sdl_scancode(Scancode::Grave), Some(Keys::Backquote));
+        assert_eq!(Keys::from_sdl_keycode(Keycode::Space), Some(Keys::Space));
+
+        // Keys without an SDL binding of this kind
+        let mut keyboard = Keyboard::default();
+        keyboard.add_key_down(Option::None);
+        keyboard.add_scancode_down(Option::None);
+        assert!(keyboard.get_keys_pressed().is_empty());
+    }
+
+    #[test]
+    fn held_duration() {
+        let mut keyboard = Keyboard::default();
+        assert_eq!(keyboard.get_held_duration(&Keys::Space), Option::None);
+
+        keyboard.add_key_down(Some(Keys::Space));
+        assert_eq!(keyboard.get_held_duration(&Keys::Space), Some(0.));
+        assert!(!keyboard.is_held(&Keys::Space));
+
+        keyboard.next_frame(0.25);
+        keyboard.next_frame(0.5);
+        assert_eq!(keyboard.get_held_duration(&Keys::Space), Some(0.75));
+        assert!(keyboard.is_held(&Keys::Space));
+
+        // Released : reset
+        keyboard.add_key_up(Some(Keys::Space));
+        assert_eq!(keyboard.get_held_duration(&Keys::Space), Option::None);
+        assert!(!keyboard.is_held(&Keys::Space));
+        keyboard.next_frame(0.5);
+
+        keyboard.add_key_down(Some(Keys::Space));
+        keyboard.next_frame(0.125);
+        assert_eq!(keyboard.get_held_duration(&Keys::Space), Some(0.125));
+    }
+
+    #[test]
+    fn key_repeats_ignored() {
+        let mut keyboard = Keyboard::default();
+        keyboard.add_key_down(Some(Keys::A));
+        keyboard.next_frame(0.5);
+
+        // Repeated by the system : still held, not pressed again
+        keyboard.add_key_down(Some(Keys::A));
+        keyboard.add_key_down(Some(Keys::A));
+        assert!(!keyboard.is_just_pressed(&Keys::A));
+        assert!(keyboard.get_keys_pressed().is_empty());
+        assert_eq!(keyboard.get_held_duration(&Keys::A), Some(0.5));
+
+        // Released twice, or without being pressed : no panic, one edge
+        keyboard.add_key_up(Some(Keys::A));
+        keyboard.add_key_up(Some(Keys::A));
+        keyboard.add_key_up(Some(Keys::B));
+        assert_eq!(keyboard.get_keys_released(), [Keys::A]);
+
+        keyboard.next_frame(0.5);
+        assert!(keyboard.get_keys_released().is_empty());
+        assert!(keyboard.is_up(&Keys::A));
+    }
+}
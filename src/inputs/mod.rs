@@ -1,2 +1,7 @@
+pub mod event;
+pub mod gamepad;
+pub mod input_map;
 pub mod keyboard;
-pub mod mouse;
\ No newline at end of file
+pub mod mouse;
+pub mod recording;
+pub mod text;
@@ -1,17 +1,91 @@
-use crate::game::common::Position;
+use sdl2::mouse::MouseButton;
+use strum_macros::{AsRefStr, Display, EnumIter, EnumString};
 
+use crate::game::common::{DeltaTime, Position};
 
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, AsRefStr, Display, EnumIter, EnumString)]
+pub enum MouseButtons {
+    Left,
+    Middle,
+    Right,
+    X1,
+    X2,
+}
+
+impl MouseButtons {
+    /*
+     * from_sdl_button()
+     *
+     * @Brief : Bind SDL MouseButton to Game2D MouseButtons
+     */
+    pub(crate) fn from_sdl_button(button: MouseButton) -> Option<MouseButtons> {
+        match button {
+            MouseButton::Left   => Some(MouseButtons::Left),
+            MouseButton::Middle => Some(MouseButtons::Middle),
+            MouseButton::Right  => Some(MouseButtons::Right),
+            MouseButton::X1     => Some(MouseButtons::X1),
+            MouseButton::X2     => Some(MouseButtons::X2),
+            _                   => Option::None,
+        }
+    }
+}
+
+pub type WheelDelta = f32;
+
+pub const MOUSE_DEFAULT_DOUBLE_CLICK_TIME: DeltaTime = 0.5;
+pub const MOUSE_DEFAULT_DOUBLE_CLICK_DISTANCE: Position = 4.;
+
+// Last button down, to recognize the second click of a double click
+struct Click {
+    button: MouseButtons,
+    time: DeltaTime,
+    x: Position,
+    y: Position,
+}
 
 pub struct Mouse {
     x: Position,
     y: Position,
+
+    // Relative motion of the frame
+    xrel: Position,
+    yrel: Position,
+
+    // Wheel of the frame
+    wheel_x: WheelDelta,
+    wheel_y: WheelDelta,
+
+    // Buttons (edges are valid for the current frame)
+    buttons_down: Vec<MouseButtons>,
+    buttons_just_pressed: Vec<MouseButtons>,
+    buttons_just_released: Vec<MouseButtons>,
+    buttons_double_clicked: Vec<MouseButtons>,
+
+    // Double click : second down of the same button, soon enough and
+    // close enough to the first one
+    time: DeltaTime,
+    last_click: Option<Click>,
+    double_click_time: DeltaTime,
+    double_click_distance: Position,
 }
 
 impl Default for Mouse {
     fn default() -> Self {
-        Mouse { 
+        Mouse {
             x: 0.,
-            y: 0. 
+            y: 0.,
+            xrel: 0.,
+            yrel: 0.,
+            wheel_x: 0.,
+            wheel_y: 0.,
+            buttons_down: Vec::new(),
+            buttons_just_pressed: Vec::new(),
+            buttons_just_released: Vec::new(),
+            buttons_double_clicked: Vec::new(),
+            time: 0.,
+            last_click: Option::None,
+            double_click_time: MOUSE_DEFAULT_DOUBLE_CLICK_TIME,
+            double_click_distance: MOUSE_DEFAULT_DOUBLE_CLICK_DISTANCE,
         }
     }
 }
@@ -21,7 +95,7 @@ impl Mouse {
         self.x = x;
     }
 
-    pub fn get_x(&mut self) -> Position {
+    pub fn get_x(&self) -> Position {
         self.x
     }
 
@@ -29,7 +103,254 @@ impl Mouse {
         self.y = y;
     }
 
-    pub fn get_y(&mut self) -> Position {
+    pub fn get_y(&self) -> Position {
         self.y
     }
-}
\ No newline at end of file
+
+    /*
+     * set_double_click()
+     *
+     * @Brief : Set the maximum time and distance between the two clicks
+     *          of a double click
+     */
+    pub fn set_double_click(&mut self, time: DeltaTime, distance: Position) -> &mut Self {
+        self.double_click_time = time;
+        self.double_click_distance = distance;
+        self
+    }
+
+    /*
+     * next_frame()
+     *
+     * @Brief : Roll the state over to a new frame (called by the game
+     *          after each frame), dt is the time of the frame
+     */
+    pub fn next_frame(&mut self, dt: DeltaTime) {
+        self.time += dt;
+        self.xrel = 0.;
+        self.yrel = 0.;
+        self.wheel_x = 0.;
+        self.wheel_y = 0.;
+        self.buttons_just_pressed.clear();
+        self.buttons_just_released.clear();
+        self.buttons_double_clicked.clear();
+    }
+
+    /*
+     * add_motion()
+     *
+     * @Brief : Add a relative motion
+     */
+    pub fn add_motion(&mut self, xrel: Position, yrel: Position) {
+        self.xrel += xrel;
+        self.yrel += yrel;
+    }
+
+    /*
+     * add_wheel()
+     *
+     * @Brief : Add a wheel motion (y > 0 : away from the user)
+     */
+    pub fn add_wheel(&mut self, x: WheelDelta, y: WheelDelta) {
+        self.wheel_x += x;
+        self.wheel_y += y;
+    }
+
+    /*
+     * add_button_down()
+     *
+     * @Brief : Add a button down at the current position, a second down of
+     *          the same button within the double click time and distance is
+     *          a double click (a third one starts a new click)
+     */
+    pub fn add_button_down(&mut self, button: Option<MouseButtons>) {
+        if let Some(button) = button {
+            if !self.buttons_down.contains(&button) {
+                self.buttons_down.push(button);
+            }
+            if !self.buttons_just_pressed.contains(&button) {
+                self.buttons_just_pressed.push(button);
+            }
+
+            let double_clicked = match &self.last_click {
+                Some(click) => click.button == button
+                    && self.time - click.time <= self.double_click_time
+                    && (self.x - click.x).hypot(self.y - click.y) <= self.double_click_distance,
+                None => false,
+            };
+            if double_clicked {
+                if !self.buttons_double_clicked.contains(&button) {
+                    self.buttons_double_clicked.push(button);
+                }
+                self.last_click = Option::None;
+            } else {
+                self.last_click = Some(Click { button, time: self.time, x: self.x, y: self.y });
+            }
+        }
+    }
+
+    /*
+     * add_button_up()
+     *
+     * @Brief : Add a button up (ignored if the button isn't down)
+     */
+    pub fn add_button_up(&mut self, button: Option<MouseButtons>) {
+        if let Some(button) = button {
+            if let Some(i) = self.buttons_down.iter().position(|b| *b == button) {
+                self.buttons_down.remove(i);
+                if !self.buttons_just_released.contains(&button) {
+                    self.buttons_just_released.push(button);
+                }
+            }
+        }
+    }
+
+    pub fn get_xrel(&self) -> Position {
+        self.xrel
+    }
+
+    pub fn get_yrel(&self) -> Position {
+        self.yrel
+    }
+
+    pub fn get_wheel_x(&self) -> WheelDelta {
+        self.wheel_x
+    }
+
+    pub fn get_wheel_y(&self) -> WheelDelta {
+        self.wheel_y
+    }
+
+    /*
+     * is_down()
+     *
+     * @Brief : Check if a button is down
+     */
+    pub fn is_down(&self, button: &MouseButtons) -> bool {
+        self.buttons_down.contains(button)
+    }
+
+    /*
+     * is_just_pressed()
+     *
+     * @Brief : Check if a button has been pressed during this frame
+     */
+    pub fn is_just_pressed(&self, button: &MouseButtons) -> bool {
+        self.buttons_just_pressed.contains(button)
+    }
+
+    /*
+     * is_just_released()
+     *
+     * @Brief : Check if a button has been released during this frame
+     */
+    pub fn is_just_released(&self, button: &MouseButtons) -> bool {
+        self.buttons_just_released.contains(button)
+    }
+
+    /*
+     * is_double_clicked()
+     *
+     * @Brief : Check if a button has been double clicked during this frame
+     */
+    pub fn is_double_clicked(&self, button: &MouseButtons) -> bool {
+        self.buttons_double_clicked.contains(button)
+    }
+
+    /*
+     * get_buttons_pressed()
+     *
+     * @Brief : Get all buttons pressed during this frame
+     */
+    pub fn get_buttons_pressed(&self) -> Vec<MouseButtons> {
+        self.buttons_just_pressed.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Button down at a time (in seconds from the start) and a position
+    fn click_at(mouse: &mut Mouse, time: DeltaTime, x: Position, y: Position) {
+        mouse.next_frame(time - mouse.time);
+        mouse.set_x(x);
+        mouse.set_y(y);
+        mouse.add_button_down(Some(MouseButtons::Left));
+        mouse.add_button_up(Some(MouseButtons::Left));
+    }
+
+    #[test]
+    fn double_click_window() {
+        let mut mouse = Mouse::default();
+        click_at(&mut mouse, 1., 10., 10.);
+        assert!(mouse.is_just_pressed(&MouseButtons::Left));
+        assert!(!mouse.is_double_clicked(&MouseButtons::Left));
+
+        click_at(&mut mouse, 1.5, 10., 10.);
+        assert!(mouse.is_double_clicked(&MouseButtons::Left));
+
+        // Only valid for the frame of the second click
+        mouse.next_frame(0.);
+        assert!(!mouse.is_double_clicked(&MouseButtons::Left));
+
+        // Too slow : the second click starts a new one
+        click_at(&mut mouse, 3., 10., 10.);
+        click_at(&mut mouse, 3.75, 10., 10.);
+        assert!(!mouse.is_double_clicked(&MouseButtons::Left));
+        click_at(&mut mouse, 4., 10., 10.);
+        assert!(mouse.is_double_clicked(&MouseButtons::Left));
+
+        // A third click isn't an other double click
+        click_at(&mut mouse, 4.25, 10., 10.);
+        assert!(!mouse.is_double_clicked(&MouseButtons::Left));
+    }
+
+    #[test]
+    fn double_click_distance() {
+        let mut mouse = Mouse::default();
+        click_at(&mut mouse, 1., 10., 10.);
+        click_at(&mut mouse, 1.25, 12., 13.);
+        assert!(mouse.is_double_clicked(&MouseButtons::Left));
+
+        // Too far : the second click starts a new one
+        click_at(&mut mouse, 2., 10., 10.);
+        click_at(&mut mouse, 2.25, 14., 14.);
+        assert!(!mouse.is_double_clicked(&MouseButtons::Left));
+        click_at(&mut mouse, 2.5, 14., 14.);
+        assert!(mouse.is_double_clicked(&MouseButtons::Left));
+
+        // Wider threshold
+        mouse.set_double_click(1., 10.);
+        click_at(&mut mouse, 3., 0., 0.);
+        click_at(&mut mouse, 3.75, 6., 8.);
+        assert!(mouse.is_double_clicked(&MouseButtons::Left));
+    }
+
+    #[test]
+    fn double_click_other_button() {
+        let mut mouse = Mouse::default();
+        click_at(&mut mouse, 1., 10., 10.);
+        mouse.add_button_down(Some(MouseButtons::Right));
+        assert!(!mouse.is_double_clicked(&MouseButtons::Right));
+        click_at(&mut mouse, 1.25, 10., 10.);
+        assert!(!mouse.is_double_clicked(&MouseButtons::Left));
+    }
+
+    #[test]
+    fn wheel_summed_and_reset() {
+        let mut mouse = Mouse::default();
+        mouse.add_wheel(0., 1.);
+        mouse.add_wheel(0.5, 2.);
+        mouse.add_wheel(-1., -0.5);
+        assert_eq!(mouse.get_wheel_x(), -0.5);
+        assert_eq!(mouse.get_wheel_y(), 2.5);
+
+        mouse.next_frame(1. / 60.);
+        assert_eq!(mouse.get_wheel_x(), 0.);
+        assert_eq!(mouse.get_wheel_y(), 0.);
+
+        mouse.add_wheel(0., -1.);
+        assert_eq!(mouse.get_wheel_y(), -1.);
+    }
+}
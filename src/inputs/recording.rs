@@ -0,0 +1,286 @@
+/*================================================================
+ *                       R E C O R D I N G
+ *
+ * Input events of a run with their frame index, saved in a compact
+ * text file and played back to reproduce the run. The fixed dt of the
+ * run (see Game::set_fixed_dt) is saved too and used by the playback :
+ *
+ *   # game2d inputs
+ *   dt 0.016666668
+ *   0 PadAdded 0 Xbox Controller
+ *   12 KeyDown Space Space -
+ *   15 KeyUp Space Space -
+ *================================================================*/
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Game2dError, Game2dResult};
+use crate::game::common::DeltaTime;
+use super::event::InputEvent;
+
+pub type Frame = u64;
+
+pub const INPUT_RECORDING_HEADER: &str = "# game2d inputs";
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct InputRecording {
+    // Sorted by frame
+    events: Vec<(Frame, InputEvent)>,
+    // dt of each frame of the run (None : the clock)
+    fixed_dt: Option<DeltaTime>,
+}
+
+#[allow(dead_code)]
+impl InputRecording {
+    pub fn new() -> InputRecording {
+        InputRecording::default()
+    }
+
+    /*
+     * push()
+     *
+     * @Brief : Add an event processed during a frame (frames must
+     *          not go backward)
+     */
+    pub fn push(&mut self, frame: Frame, event: InputEvent) {
+        let frame = match self.events.last() {
+            Some((last, _)) => frame.max(*last),
+            None => frame,
+        };
+        self.events.push((frame, event));
+    }
+
+    pub fn get_events(&self) -> &[(Frame, InputEvent)] {
+        &self.events
+    }
+
+    /*
+     * set_fixed_dt()
+     *
+     * @Brief : dt of the frames recorded (forced during the playback)
+     */
+    pub fn set_fixed_dt(&mut self, fixed_dt: Option<DeltaTime>) -> &mut Self {
+        self.fixed_dt = fixed_dt;
+        self
+    }
+
+    pub fn get_fixed_dt(&self) -> Option<DeltaTime> {
+        self.fixed_dt
+    }
+
+    /*
+     * get_last_frame()
+     *
+     * @Brief : Frame of the last event
+     */
+    pub fn get_last_frame(&self) -> Option<Frame> {
+        self.events.last().map(|(frame, _)| *frame)
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /*
+     * load()
+     *
+     * @Brief : Load a recording from a file
+     */
+    pub fn load(filename: &str) -> Game2dResult<InputRecording> {
+        let recording = std::fs::read_to_string(filename)
+            .map_err(|e| Game2dError::AssetIo { path: filename.to_string(), message: e.to_string() })?;
+        InputRecording::from_str(&recording)
+            .map_err(|message| Game2dError::Decode { path: filename.to_string(), message })
+    }
+
+    /*
+     * save()
+     *
+     * @Brief : Save the recording in a file
+     */
+    pub fn save(&self, filename: &str) -> Game2dResult<()> {
+        std::fs::write(filename, self.to_string())
+            .map_err(|e| Game2dError::AssetIo { path: filename.to_string(), message: e.to_string() })
+    }
+}
+
+impl fmt::Display for InputRecording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", INPUT_RECORDING_HEADER)?;
+        if let Some(fixed_dt) = self.fixed_dt {
+            writeln!(f, "dt {}", fixed_dt)?;
+        }
+        for (frame, event) in self.events.iter() {
+            writeln!(f, "{} {}", frame, event)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for InputRecording {
+    type Err = String;
+
+    fn from_str(recording: &str) -> Result<Self, Self::Err> {
+        let mut events = InputRecording::new();
+
+        for (number, line) in recording.lines().enumerate() {
+            // Texts may end with spaces : only empty lines are skipped
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |e: String| format!("Line {} : {}", number + 1, e);
+
+            if let Some(fixed_dt) = line.strip_prefix("dt ") {
+                let fixed_dt = fixed_dt
+                    .trim()
+                    .parse()
+                    .map_err(|_| error(format!("Bad dt {}", fixed_dt)))?;
+                events.set_fixed_dt(Some(fixed_dt));
+                continue;
+            }
+
+            let (frame, event) = line
+                .split_once(' ')
+                .ok_or_else(|| error(String::from("<frame> <event> expected")))?;
+            let frame: Frame = frame
+                .parse()
+                .map_err(|_| error(format!("Bad frame {}", frame)))?;
+            events.push(frame, InputEvent::from_str(event).map_err(error)?);
+        }
+
+        Ok(events)
+    }
+}
+
+/*================================================================
+ *                        P L A Y B A C K
+ *================================================================*/
+pub struct InputPlayback {
+    recording: InputRecording,
+    position: usize,
+}
+
+#[allow(dead_code)]
+impl InputPlayback {
+    pub fn new(recording: InputRecording) -> InputPlayback {
+        InputPlayback { recording, position: 0 }
+    }
+
+    /*
+     * get_events()
+     *
+     * @Brief : Events to process in a frame (and the skipped frames
+     *          before)
+     */
+    pub fn get_events(&mut self, frame: Frame) -> Vec<InputEvent> {
+        let mut events = Vec::new();
+        while let Some((event_frame, event)) = self.recording.events.get(self.position) {
+            if *event_frame > frame {
+                break;
+            }
+            events.push(event.clone());
+            self.position += 1;
+        }
+        events
+    }
+
+    /*
+     * is_finished()
+     *
+     * @Brief : All the events have been played
+     */
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.recording.len()
+    }
+
+    pub fn rewind(&mut self) {
+        self.position = 0;
+    }
+
+    /*
+     * get_fixed_dt()
+     *
+     * @Brief : dt of the frames of the recording
+     */
+    pub fn get_fixed_dt(&self) -> Option<DeltaTime> {
+        self.recording.get_fixed_dt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::gamepad::{GamepadAxes, GamepadButtons};
+    use crate::inputs::keyboard::{Keys, Modifiers};
+    use crate::inputs::mouse::MouseButtons;
+
+    fn recording() -> InputRecording {
+        let mut recording = InputRecording::new();
+        recording.set_fixed_dt(Some(1. / 60.));
+        for (frame, event) in [
+            (0, InputEvent::GamepadConnected { id: 0, name: String::from("Xbox Controller") }),
+            (2, InputEvent::KeyDown { key: Some(Keys::Space), scancode: Some(Keys::Space), modifiers: Modifiers { ctrl: true, ..Default::default() } }),
+            (2, InputEvent::KeyUp { key: Option::None, scancode: Some(Keys::A), modifiers: Modifiers::default() }),
+            (3, InputEvent::MouseMotion { x: 10.5, y: 20., xrel: -1., yrel: 2.25 }),
+            (3, InputEvent::MouseButtonDown { button: Some(MouseButtons::Left), x: 10.5, y: 20. }),
+            (4, InputEvent::MouseButtonUp { button: Option::None, x: 0., y: 0. }),
+            (4, InputEvent::MouseWheel { x: 0., y: -1. }),
+            (5, InputEvent::TextInput(String::from("a b \\ \n "))),
+            (5, InputEvent::TextEditing { text: String::from("kana"), start: 1, length: 2 }),
+            (6, InputEvent::GamepadButtonDown { id: 0, button: GamepadButtons::A }),
+            (6, InputEvent::GamepadButtonUp { id: 0, button: GamepadButtons::Start }),
+            (7, InputEvent::GamepadAxis { id: 0, axis: GamepadAxes::TriggerLeft, value: 0.75 }),
+            (8, InputEvent::GamepadDisconnected(0)),
+            (9, InputEvent::Quit),
+        ] {
+            recording.push(frame, event);
+        }
+        recording
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let recording = recording();
+        let text = recording.to_string();
+        assert!(text.starts_with(INPUT_RECORDING_HEADER));
+        assert_eq!(InputRecording::from_str(&text).unwrap(), recording);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let recording = recording();
+        let filename = std::env::temp_dir().join(format!("game2d_recording_{}.txt", std::process::id()));
+        let filename = filename.to_string_lossy();
+
+        recording.save(&filename).unwrap();
+        let loaded = InputRecording::load(&filename);
+        let _ = std::fs::remove_file(filename.as_ref());
+        assert_eq!(loaded.unwrap(), recording);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(InputRecording::from_str("x KeyDown Space Space -").is_err());
+        assert!(InputRecording::from_str("1 Jump").is_err());
+        assert!(InputRecording::from_str("dt fast").is_err());
+    }
+
+    #[test]
+    fn playback_by_frame() {
+        let mut playback = InputPlayback::new(recording());
+        assert_eq!(playback.get_fixed_dt(), Some(1. / 60.));
+        assert_eq!(playback.get_events(0).len(), 1);
+        assert!(playback.get_events(1).is_empty());
+        // Skipped frames : their events come with the next one
+        assert_eq!(playback.get_events(4).len(), 6);
+        assert_eq!(playback.get_events(9).len(), 7);
+        assert!(playback.is_finished());
+
+        playback.rewind();
+        assert!(!playback.is_finished());
+    }
+}
@@ -0,0 +1,594 @@
+/*================================================================
+ *                            T E X T
+ *
+ * Text input mode (name entry, chat...) : UTF-8 text typed, IME
+ * composition and editing keys, applied to a TextBuffer.
+ *================================================================*/
+use super::keyboard::{Keys, Modifiers};
+
+/*
+ * TextEdit : editing key received while the text input is active
+ *            (key repeats included)
+ */
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum TextEdit {
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    // Move the cursor and extend the selection (Shift)
+    SelectLeft,
+    SelectRight,
+    SelectHome,
+    SelectEnd,
+    SelectAll,
+}
+
+impl TextEdit {
+    /*
+     * from_key()
+     *
+     * @Brief : Editing key bound to a key and the modifiers held
+     */
+    pub(crate) fn from_key(key: &Keys, modifiers: Modifiers) -> Option<TextEdit> {
+        match (key, modifiers.shift) {
+            (Keys::Backspace, _) => Some(TextEdit::Backspace),
+            (Keys::Delete, _)    => Some(TextEdit::Delete),
+            (Keys::Left, false)  => Some(TextEdit::Left),
+            (Keys::Right, false) => Some(TextEdit::Right),
+            (Keys::Home, false)  => Some(TextEdit::Home),
+            (Keys::End, false)   => Some(TextEdit::End),
+            (Keys::Left, true)   => Some(TextEdit::SelectLeft),
+            (Keys::Right, true)  => Some(TextEdit::SelectRight),
+            (Keys::Home, true)   => Some(TextEdit::SelectHome),
+            (Keys::End, true)    => Some(TextEdit::SelectEnd),
+            (Keys::A, _) if modifiers.ctrl => Some(TextEdit::SelectAll),
+            _                    => Option::None,
+        }
+    }
+}
+
+/*
+ * TextEvent : text typed or edit, in the order received
+ */
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum TextEvent {
+    Text(String),
+    Edit(TextEdit),
+}
+
+/*
+ * Composition : text being composed with an IME (not committed yet)
+ */
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct Composition {
+    pub text: String,
+    // Cursor and selection in the composition (in chars)
+    pub start: usize,
+    pub length: usize,
+}
+
+/*================================================================
+ *                      T E X T   I N P U T
+ *================================================================*/
+#[derive(Default)]
+pub struct TextInput {
+    active: bool,
+
+    // Events of the frame
+    events: Vec<TextEvent>,
+
+    // Composition in progress (kept between frames)
+    composition: Option<Composition>,
+}
+
+#[allow(dead_code)]
+impl TextInput {
+    /*
+     * start()
+     *
+     * @Brief : Start receiving text (opens the IME / on-screen keyboard)
+     */
+    pub fn start(&mut self) {
+        self.active = true;
+    }
+
+    /*
+     * stop()
+     *
+     * @Brief : Stop receiving text
+     */
+    pub fn stop(&mut self) {
+        self.active = false;
+        self.events.clear();
+        self.composition = Option::None;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /*
+     * next_frame()
+     *
+     * @Brief : Roll the state over to a new frame (called by the game
+     *          after each frame)
+     */
+    pub fn next_frame(&mut self) {
+        self.events.clear();
+    }
+
+    /*
+     * add_text()
+     *
+     * @Brief : Add text committed (ends the composition)
+     */
+    pub fn add_text(&mut self, text: &str) {
+        if self.active && !text.is_empty() {
+            self.composition = Option::None;
+            self.events.push(TextEvent::Text(text.to_string()));
+        }
+    }
+
+    /*
+     * add_edit()
+     *
+     * @Brief : Add an editing key
+     */
+    pub fn add_edit(&mut self, edit: TextEdit) {
+        if self.active && self.composition.is_none() {
+            self.events.push(TextEvent::Edit(edit));
+        }
+    }
+
+    /*
+     * set_composition()
+     *
+     * @Brief : Set the IME composition (empty text : no composition)
+     */
+    pub fn set_composition(&mut self, text: &str, start: usize, length: usize) {
+        if !self.active {
+            return;
+        }
+        self.composition = if text.is_empty() {
+            Option::None
+        } else {
+            Some(Composition { text: text.to_string(), start, length })
+        };
+    }
+
+    /*
+     * get_events()
+     *
+     * @Brief : Text and edits received during this frame
+     */
+    pub fn get_events(&self) -> &[TextEvent] {
+        &self.events
+    }
+
+    /*
+     * get_text()
+     *
+     * @Brief : Text committed during this frame
+     */
+    pub fn get_text(&self) -> String {
+        self.events
+            .iter()
+            .filter_map(|e| match e {
+                TextEvent::Text(text) => Some(text.as_str()),
+                TextEvent::Edit(_) => Option::None,
+            })
+            .collect()
+    }
+
+    pub fn get_composition(&self) -> Option<&Composition> {
+        self.composition.as_ref()
+    }
+
+    /*
+     * apply()
+     *
+     * @Brief : Apply the text and edits of this frame to a buffer
+     */
+    pub fn apply(&self, buffer: &mut TextBuffer) {
+        for event in self.events.iter() {
+            match event {
+                TextEvent::Text(text) => buffer.insert(text),
+                TextEvent::Edit(edit) => buffer.edit(*edit),
+            }
+        }
+    }
+}
+
+/*================================================================
+ *                     T E X T   B U F F E R
+ *================================================================*/
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct TextBuffer {
+    text: String,
+
+    // Cursor position in chars
+    cursor: usize,
+
+    // Selection : from the anchor to the cursor (in chars)
+    anchor: Option<usize>,
+
+    // Max length in chars
+    max_length: Option<usize>,
+}
+
+#[allow(dead_code)]
+impl TextBuffer {
+    pub fn new() -> TextBuffer {
+        TextBuffer::default()
+    }
+
+    /*
+     * set_max_length()
+     *
+     * @Brief : Max length of the text in chars (None : no limit)
+     */
+    pub fn set_max_length(&mut self, max_length: Option<usize>) -> &mut Self {
+        self.max_length = max_length;
+        if let Some(max_length) = max_length {
+            if self.len() > max_length {
+                self.text = self.text.chars().take(max_length).collect();
+                self.cursor = self.cursor.min(max_length);
+                self.anchor = self.anchor.map(|anchor| anchor.min(max_length));
+            }
+        }
+        self
+    }
+
+    /*
+     * set_text()
+     *
+     * @Brief : Replace the text, cursor at the end
+     */
+    pub fn set_text(&mut self, text: &str) -> &mut Self {
+        self.clear();
+        self.insert(text);
+        self
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /*
+     * get_cursor()
+     *
+     * @Brief : Cursor position in chars
+     */
+    pub fn get_cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /*
+     * get_text_before_cursor()
+     *
+     * @Brief : Text on the left of the cursor (to place the caret)
+     */
+    pub fn get_text_before_cursor(&self) -> &str {
+        &self.text[..self.byte_index(self.cursor)]
+    }
+
+    /*
+     * select()
+     *
+     * @Brief : Select the chars from start to end (the cursor goes
+     *          to end)
+     */
+    pub fn select(&mut self, start: usize, end: usize) -> &mut Self {
+        let len = self.len();
+        self.anchor = Some(start.min(len));
+        self.cursor = end.min(len);
+        self
+    }
+
+    pub fn select_all(&mut self) -> &mut Self {
+        self.select(0, self.len())
+    }
+
+    /*
+     * get_selection()
+     *
+     * @Brief : Chars selected, start and end (None : no selection)
+     */
+    pub fn get_selection(&self) -> Option<(usize, usize)> {
+        self.anchor
+            .filter(|anchor| *anchor != self.cursor)
+            .map(|anchor| (anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+
+    pub fn get_selected_text(&self) -> &str {
+        match self.get_selection() {
+            Some((start, end)) => &self.text[self.byte_index(start)..self.byte_index(end)],
+            None => "",
+        }
+    }
+
+    /*
+     * len()
+     *
+     * @Brief : Length of the text in chars
+     */
+    pub fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+        self.anchor = Option::None;
+    }
+
+    /*
+     * insert()
+     *
+     * @Brief : Insert text at the cursor, in place of the selection
+     *          (cut to the max length)
+     */
+    pub fn insert(&mut self, text: &str) {
+        self.delete_selection();
+        let room = match self.max_length {
+            Some(max_length) => max_length.saturating_sub(self.len()),
+            None => usize::MAX,
+        };
+        let text: String = text.chars().filter(|c| !c.is_control()).take(room).collect();
+        let index = self.byte_index(self.cursor);
+        self.text.insert_str(index, &text);
+        self.cursor += text.chars().count();
+    }
+
+    /*
+     * edit()
+     *
+     * @Brief : Apply an editing key
+     */
+    pub fn edit(&mut self, edit: TextEdit) {
+        let selection = self.get_selection();
+        self.update_anchor(edit);
+        match (edit, selection) {
+            (TextEdit::Backspace | TextEdit::Delete, Some((start, end))) => self.delete_range(start, end),
+            (TextEdit::Backspace, None) => {
+                if self.cursor > 0 {
+                    self.delete_range(self.cursor - 1, self.cursor);
+                }
+            },
+            (TextEdit::Delete, None) => {
+                if self.cursor < self.len() {
+                    self.delete_range(self.cursor, self.cursor + 1);
+                }
+            },
+            // With a selection : go to its start or its end
+            (TextEdit::Left, Some((start, _))) => self.cursor = start,
+            (TextEdit::Right, Some((_, end))) => self.cursor = end,
+            (TextEdit::Left | TextEdit::SelectLeft, _) => self.cursor = self.cursor.saturating_sub(1),
+            (TextEdit::Right | TextEdit::SelectRight, _) => self.cursor = (self.cursor + 1).min(self.len()),
+            (TextEdit::Home | TextEdit::SelectHome, _) => self.cursor = 0,
+            (TextEdit::End | TextEdit::SelectEnd, _) => self.cursor = self.len(),
+            (TextEdit::SelectAll, _) => {
+                self.select_all();
+            },
+        }
+    }
+
+    /*
+     * update_anchor()
+     *
+     * @Brief : Keep the selection of the select edits, drop it for
+     *          the others (called before the cursor moves)
+     */
+    fn update_anchor(&mut self, edit: TextEdit) {
+        match edit {
+            TextEdit::SelectLeft | TextEdit::SelectRight | TextEdit::SelectHome | TextEdit::SelectEnd => {
+                self.anchor.get_or_insert(self.cursor);
+            },
+            TextEdit::SelectAll => {},
+            _ => self.anchor = Option::None,
+        }
+    }
+
+    /*
+     * delete_selection()
+     *
+     * @Brief : Remove the chars selected (cursor at their place)
+     */
+    fn delete_selection(&mut self) {
+        if let Some((start, end)) = self.get_selection() {
+            self.delete_range(start, end);
+        }
+        self.anchor = Option::None;
+    }
+
+    fn delete_range(&mut self, start: usize, end: usize) {
+        let range = self.byte_index(start)..self.byte_index(end);
+        self.text.replace_range(range, "");
+        self.cursor = start;
+    }
+
+    /*
+     * byte_index()
+     *
+     * @Brief : Byte index in the string of a char position
+     */
+    fn byte_index(&self, position: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(position)
+            .map(|(i, _)| i)
+            .unwrap_or(self.text.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(text: &str) -> TextBuffer {
+        let mut buffer = TextBuffer::new();
+        buffer.set_text(text);
+        buffer
+    }
+
+    fn active_input() -> TextInput {
+        let mut input = TextInput::default();
+        input.start();
+        input
+    }
+
+    #[test]
+    fn insert_and_delete_multibyte_chars() {
+        let mut buffer = buffer("héllo");
+        assert_eq!((buffer.len(), buffer.get_cursor()), (5, 5));
+
+        buffer.edit(TextEdit::Home);
+        buffer.edit(TextEdit::Right);
+        buffer.edit(TextEdit::Right);
+        assert_eq!(buffer.get_text_before_cursor(), "hé");
+
+        buffer.edit(TextEdit::Backspace);
+        assert_eq!((buffer.get_text(), buffer.get_cursor()), ("hllo", 1));
+        buffer.insert("ë日本");
+        assert_eq!((buffer.get_text(), buffer.get_cursor()), ("hë日本llo", 4));
+        buffer.edit(TextEdit::Delete);
+        assert_eq!((buffer.get_text(), buffer.get_cursor()), ("hë日本lo", 4));
+        assert_eq!(buffer.get_text_before_cursor(), "hë日本");
+
+        // Control chars are dropped
+        buffer.insert("\t!\n");
+        assert_eq!(buffer.get_text(), "hë日本!lo");
+    }
+
+    #[test]
+    fn cursor_at_both_ends() {
+        let mut buffer = buffer("ab");
+        buffer.edit(TextEdit::Right);
+        buffer.edit(TextEdit::Delete);
+        assert_eq!((buffer.get_text(), buffer.get_cursor()), ("ab", 2));
+
+        buffer.edit(TextEdit::Home);
+        buffer.edit(TextEdit::Left);
+        buffer.edit(TextEdit::Backspace);
+        assert_eq!((buffer.get_text(), buffer.get_cursor()), ("ab", 0));
+
+        buffer.edit(TextEdit::End);
+        assert_eq!(buffer.get_cursor(), 2);
+        buffer.clear();
+        buffer.edit(TextEdit::Backspace);
+        buffer.edit(TextEdit::Right);
+        assert_eq!((buffer.get_text(), buffer.get_cursor()), ("", 0));
+    }
+
+    #[test]
+    fn selection_replaced() {
+        let mut buffer = buffer("bonjour");
+        buffer.edit(TextEdit::Home);
+        buffer.edit(TextEdit::SelectRight);
+        buffer.edit(TextEdit::SelectRight);
+        buffer.edit(TextEdit::SelectRight);
+        assert_eq!(buffer.get_selection(), Some((0, 3)));
+        assert_eq!(buffer.get_selected_text(), "bon");
+
+        buffer.insert("a");
+        assert_eq!((buffer.get_text(), buffer.get_cursor()), ("ajour", 1));
+        assert_eq!(buffer.get_selection(), Option::None);
+
+        // Selected backward, deleted
+        buffer.edit(TextEdit::SelectEnd);
+        buffer.edit(TextEdit::SelectLeft);
+        assert_eq!(buffer.get_selected_text(), "jou");
+        buffer.edit(TextEdit::Backspace);
+        assert_eq!((buffer.get_text(), buffer.get_cursor()), ("ar", 1));
+
+        // A move drops the selection (to its start)
+        buffer.edit(TextEdit::SelectAll);
+        assert_eq!(buffer.get_selection(), Some((0, 2)));
+        buffer.edit(TextEdit::Left);
+        assert_eq!((buffer.get_selection(), buffer.get_cursor()), (Option::None, 0));
+
+        buffer.select(1, 2).insert("ïe");
+        assert_eq!(buffer.get_text(), "aïe");
+        buffer.select(3, 0).edit(TextEdit::Delete);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn max_length() {
+        let mut buffer = buffer("abcdef");
+        buffer.set_max_length(Some(4));
+        assert_eq!((buffer.get_text(), buffer.get_cursor()), ("abcd", 4));
+
+        buffer.insert("xyz");
+        assert_eq!(buffer.get_text(), "abcd");
+
+        // Room made by the selection replaced
+        buffer.select(1, 3).insert("ééé");
+        assert_eq!((buffer.get_text(), buffer.get_cursor()), ("aééd", 3));
+
+        buffer.set_max_length(Option::None).insert("zz");
+        assert_eq!(buffer.get_text(), "aéézzd");
+    }
+
+    #[test]
+    fn edits_from_keys() {
+        let shift = Modifiers { shift: true, ..Modifiers::NONE };
+        let ctrl = Modifiers { ctrl: true, ..Modifiers::NONE };
+        assert_eq!(TextEdit::from_key(&Keys::Left, Modifiers::NONE), Some(TextEdit::Left));
+        assert_eq!(TextEdit::from_key(&Keys::Left, shift), Some(TextEdit::SelectLeft));
+        assert_eq!(TextEdit::from_key(&Keys::End, shift), Some(TextEdit::SelectEnd));
+        assert_eq!(TextEdit::from_key(&Keys::Backspace, shift), Some(TextEdit::Backspace));
+        assert_eq!(TextEdit::from_key(&Keys::A, ctrl), Some(TextEdit::SelectAll));
+        assert_eq!(TextEdit::from_key(&Keys::A, Modifiers::NONE), Option::None);
+    }
+
+    #[test]
+    fn composition_committed() {
+        let mut input = active_input();
+        input.set_composition("にほ", 2, 0);
+        assert_eq!(input.get_composition(), Some(&Composition { text: String::from("にほ"), start: 2, length: 0 }));
+
+        // Editing keys go to the IME while composing
+        input.add_edit(TextEdit::Backspace);
+        assert!(input.get_events().is_empty());
+
+        input.add_text("日本");
+        assert_eq!(input.get_composition(), Option::None);
+        assert_eq!(input.get_text(), "日本");
+
+        let mut buffer = buffer("!");
+        buffer.edit(TextEdit::Home);
+        input.apply(&mut buffer);
+        assert_eq!(buffer.get_text(), "日本!");
+
+        input.next_frame();
+        assert!(input.get_events().is_empty());
+    }
+
+    #[test]
+    fn composition_cancelled() {
+        let mut input = active_input();
+        input.set_composition("にほ", 2, 0);
+        input.next_frame();
+        // Kept between frames
+        assert!(input.get_composition().is_some());
+
+        input.set_composition("", 0, 0);
+        assert_eq!(input.get_composition(), Option::None);
+        assert_eq!(input.get_text(), "");
+
+        // Stopped : nothing is received
+        input.stop();
+        input.add_text("a");
+        input.set_composition("か", 1, 0);
+        assert!(input.get_events().is_empty());
+        assert_eq!(input.get_composition(), Option::None);
+    }
+}
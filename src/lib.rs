@@ -1,5 +1,5 @@
-
 pub mod animations;
+pub mod error;
 pub mod graphics;
 pub mod game;
 pub mod inputs;
\ No newline at end of file
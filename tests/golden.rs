@@ -0,0 +1,87 @@
+/*================================================================
+ *                          G O L D E N
+ *
+ * Frames rendered headless compared with the references of
+ * tests/snapshots. To record them again after a wanted change :
+ *
+ *   GAME2D_UPDATE_SNAPSHOTS=1 cargo test --test golden
+ *================================================================*/
+use game2d::graphics::color::Color;
+use game2d::graphics::fonts::FontsManager;
+use game2d::graphics::graphics::{DrawMode, Graphics};
+use game2d::graphics::snapshot::{assert_snapshot, Snapshot, SNAPSHOT_UPDATE_ENV};
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+
+#[test]
+fn golden_rectangle() {
+    let mut graphics = Graphics::new_headless(WIDTH, HEIGHT).unwrap();
+    graphics.set_background_color(Color::BLACK);
+
+    assert_snapshot(&mut graphics, "tests/snapshots/rectangle.png", 0, |g| {
+        g.rectangle(DrawMode::Fill, 4., 4., 20, 12, Some(Color::RED))?;
+        g.rectangle(DrawMode::Line, 30., 8., 24, 30, Some(Color::WHITE))
+    });
+}
+
+#[test]
+fn golden_line() {
+    let mut graphics = Graphics::new_headless(WIDTH, HEIGHT).unwrap();
+    graphics.set_background_color(Color::BLACK);
+
+    assert_snapshot(&mut graphics, "tests/snapshots/line.png", 0, |g| {
+        g.line(2., 2., 60., 2., Some(Color::GREEN))?;
+        g.line(2., 40., 60., 10., Some(Color::WHITE))
+    });
+}
+
+#[test]
+fn golden_draw_flipped() {
+    let mut graphics = Graphics::new_headless(WIDTH, HEIGHT).unwrap();
+    graphics.set_background_color(Color::BLACK);
+    // 8x8 : red, green / blue, white quarters
+    let sprite = graphics.new_image("tests/assets/sprite.png").unwrap();
+
+    assert_snapshot(&mut graphics, "tests/snapshots/draw_flipped.png", 0, |g| {
+        g.draw_full(&sprite, 4., 4., 0., 2., 2., 0., 0.)?;
+        g.draw_full(&sprite, 24., 4., 0., -2., 2., 0., 0.)?;
+        g.draw_full(&sprite, 44., 4., 0., 2., -2., 0., 0.)?;
+        g.draw_full(&sprite, 24., 26., 0., -2., -2., 0., 0.)
+    });
+}
+
+#[test]
+fn golden_print() {
+    let mut graphics = Graphics::new_headless(WIDTH, HEIGHT).unwrap();
+    graphics.set_background_color(Color::BLACK);
+
+    // Bitmap font : TTF glyphs differ between SDL_ttf / FreeType versions
+    let mut fonts_manager = FontsManager::new(graphics.get_fonts_creator());
+    let font = graphics.load_grid_font(&mut fonts_manager, "tests/assets/font.png", 4, 6, "Hi!?").unwrap();
+    graphics.set_font(font);
+
+    assert_snapshot(&mut graphics, "tests/snapshots/print.png", 0, |g| {
+        g.print(&mut fonts_manager, String::from("Hi!"), 4., 4., Some(Color::WHITE))?;
+        g.print(&mut fonts_manager, String::from("Hi?"), 4., 14., Some(Color::GREEN))?;
+        g.print_full(&mut fonts_manager, String::from("iH"), 36., 24., Some(Color::RED), 0., 3., 2., 0., 0.)
+    });
+}
+
+#[test]
+fn golden_missing_reference() {
+    // Recording the references : this one must stay missing
+    if std::env::var_os(SNAPSHOT_UPDATE_ENV).is_some() {
+        return;
+    }
+    let mut graphics = Graphics::new_headless(WIDTH, HEIGHT).unwrap();
+    let snapshot = Snapshot::new("tests/snapshots/missing.png");
+
+    let checked = snapshot.check(&mut graphics, |g| g.line(0., 0., 10., 10., Some(Color::WHITE)));
+    assert!(checked.is_err());
+    assert!(!std::path::Path::new("tests/snapshots/missing.png").exists());
+
+    // The frame is kept to be looked at
+    assert!(snapshot.get_actual_path().exists());
+    std::fs::remove_file(snapshot.get_actual_path()).unwrap();
+}
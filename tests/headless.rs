@@ -0,0 +1,118 @@
+/*================================================================
+ *                       H E A D L E S S
+ *
+ * Draw with a Graphics without window and read the pixels back
+ *================================================================*/
+use game2d::game::common::Position2d;
+use game2d::graphics::color::Color;
+use game2d::graphics::graphics::{DrawMode, Graphics};
+
+#[test]
+fn headless_draw_and_read_pixels() {
+    let mut graphics = Graphics::new_headless(16, 16).unwrap();
+    assert_eq!(graphics.get_output_size(), Some((16, 16)));
+
+    graphics.rectangle(DrawMode::Fill, 2., 2., 4, 4, Some(Color::RED)).unwrap();
+    graphics.line(0., 10., 15., 10., Some(Color::GREEN)).unwrap();
+
+    // Cleared in black on creation
+    assert_eq!(graphics.get_pixel(0, 0), Some(Color::BLACK));
+    assert_eq!(graphics.get_pixel(15, 15), Some(Color::BLACK));
+
+    // Rectangle : [2, 6[
+    assert_eq!(graphics.get_pixel(2, 2), Some(Color::RED));
+    assert_eq!(graphics.get_pixel(5, 5), Some(Color::RED));
+    assert_eq!(graphics.get_pixel(6, 6), Some(Color::BLACK));
+
+    // Line
+    assert_eq!(graphics.get_pixel(0, 10), Some(Color::GREEN));
+    assert_eq!(graphics.get_pixel(8, 10), Some(Color::GREEN));
+    assert_eq!(graphics.get_pixel(8, 11), Some(Color::BLACK));
+
+    // Outside the output
+    assert_eq!(graphics.get_pixel(16, 0), Option::None);
+
+    let pixels = graphics.read_pixels().unwrap();
+    assert_eq!(pixels.len(), 16 * 16 * 4);
+    assert_eq!(&pixels[(2 * 16 + 2) * 4..(2 * 16 + 3) * 4], &[255, 0, 0, 255]);
+}
+
+#[test]
+fn headless_without_vsync() {
+    let graphics = Graphics::new_headless(16, 16).unwrap();
+    assert!(!graphics.is_vsync());
+}
+
+#[test]
+fn headless_render_target_read_back() {
+    let mut graphics = Graphics::new_headless(16, 16).unwrap();
+    let target = graphics.new_render_target("canvas", 8, 8).unwrap();
+
+    graphics.set_target(&target).unwrap();
+    graphics.clear(Some(Color::BLUE));
+    graphics.rectangle(DrawMode::Fill, 0., 0., 4, 4, Some(Color::RED)).unwrap();
+    graphics.reset_target().unwrap();
+
+    graphics.draw(&target, 4., 4., 0.).unwrap();
+    assert_eq!(graphics.get_pixel(4, 4), Some(Color::RED));
+    assert_eq!(graphics.get_pixel(7, 7), Some(Color::RED));
+    assert_eq!(graphics.get_pixel(8, 8), Some(Color::BLUE));
+    assert_eq!(graphics.get_pixel(11, 11), Some(Color::BLUE));
+    assert_eq!(graphics.get_pixel(12, 12), Some(Color::BLACK));
+    assert_eq!(graphics.get_pixel(3, 3), Some(Color::BLACK));
+
+    // Replaced by a smaller one (the old texture is destroyed)
+    let target = graphics.new_render_target("canvas", 2, 2).unwrap();
+    graphics.set_target(&target).unwrap();
+    graphics.clear(Some(Color::GREEN));
+    graphics.reset_target().unwrap();
+
+    graphics.clear(Some(Color::BLACK));
+    graphics.draw(&target, 0., 0., 0.).unwrap();
+    assert_eq!(graphics.get_pixel(1, 1), Some(Color::GREEN));
+    assert_eq!(graphics.get_pixel(2, 2), Some(Color::BLACK));
+}
+
+#[test]
+fn headless_render_target_named_like_an_image() {
+    let mut graphics = Graphics::new_headless(16, 16).unwrap();
+    // 8x8 : red, green / blue, white quarters
+    let sprite = graphics.new_image("tests/assets/sprite.png").unwrap();
+    let target = graphics.new_render_target("tests/assets/sprite.png", 8, 8).unwrap();
+
+    graphics.set_target(&target).unwrap();
+    assert_eq!(graphics.get_target(), Some("tests/assets/sprite.png"));
+    graphics.clear(Some(Color::BLUE));
+    graphics.reset_target().unwrap();
+
+    // The image and the target are both kept
+    graphics.draw(&sprite, 0., 0., 0.).unwrap();
+    graphics.draw(&target, 8., 8., 0.).unwrap();
+    assert_eq!(graphics.get_pixel(0, 0), Some(Color::RED));
+    assert_eq!(graphics.get_pixel(7, 7), Some(Color::WHITE));
+    assert_eq!(graphics.get_pixel(8, 8), Some(Color::BLUE));
+    assert_eq!(graphics.get_pixel(15, 15), Some(Color::BLUE));
+}
+
+#[test]
+fn headless_far_polygon_not_wrapped() {
+    let mut graphics = Graphics::new_headless(32, 32).unwrap();
+    let far = 65546.;
+
+    // Beyond the i16 range of the gfx primitives : 65546 would wrap to 10
+    graphics.polygon(DrawMode::Fill, &[
+        Position2d { x: 0., y: 0. },
+        Position2d { x: far, y: 0. },
+        Position2d { x: 0., y: far },
+    ], Some(Color::RED)).unwrap();
+    graphics.polygon(DrawMode::Fill, &[
+        Position2d { x: far, y: 20. },
+        Position2d { x: far + 10., y: 20. },
+        Position2d { x: far, y: 30. },
+    ], Some(Color::GREEN)).unwrap();
+
+    // The big triangle covers the output, the far one stays off-screen
+    assert_eq!(graphics.get_pixel(20, 20), Some(Color::RED));
+    assert_eq!(graphics.get_pixel(31, 0), Some(Color::RED));
+    assert_eq!(graphics.get_pixel(12, 22), Some(Color::RED));
+}
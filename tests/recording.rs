@@ -0,0 +1,132 @@
+/*================================================================
+ *                        R E C O R D I N G
+ *
+ * Draw calls of Graphics logged by a RecordingRenderer
+ *================================================================*/
+use sdl2::rect::{Point, Rect};
+
+use game2d::game::common::Position2d;
+use game2d::graphics::canvas::SdlCanvas;
+use game2d::graphics::color::Color;
+use game2d::graphics::graphics::{DrawMode, Graphics};
+use game2d::graphics::renderer::{DrawCommand, RecordingRenderer};
+
+#[test]
+fn recording_logs_draw_calls() {
+    let recording = RecordingRenderer::new(Box::new(SdlCanvas::new_headless(32, 32).unwrap()));
+    let log = recording.get_log();
+    let mut graphics = Graphics::with_renderer(Box::new(recording));
+
+    // Cleared and presented on creation
+    assert_eq!(log.get_commands(), vec![DrawCommand::Clear(Color::BLACK), DrawCommand::Present]);
+    log.clear();
+
+    graphics.rectangle(DrawMode::Fill, 2., 3., 10, 5, Some(Color::RED)).unwrap();
+    graphics.push();
+    graphics.translate(4., 4.);
+    graphics.line(0., 0., 8., 0., Some(Color::GREEN)).unwrap();
+    graphics.pop().unwrap();
+
+    assert_eq!(log.get_commands(), vec![
+        DrawCommand::Rectangle { mode: DrawMode::Fill, rect: Rect::new(2, 3, 10, 5), color: Color::RED },
+        DrawCommand::Line { start: Point::new(4, 4), end: Point::new(12, 4), color: Color::GREEN },
+    ]);
+}
+
+#[test]
+fn recording_last_frame() {
+    let recording = RecordingRenderer::new(Box::new(SdlCanvas::new_headless(32, 32).unwrap()));
+    let log = recording.get_log();
+    let mut graphics = Graphics::with_renderer(Box::new(recording));
+
+    graphics.clear(Some(Color::BLUE));
+    graphics.line(1., 1., 5., 1., Some(Color::WHITE)).unwrap();
+
+    assert_eq!(log.get_last_frame(), vec![
+        DrawCommand::Clear(Color::BLUE),
+        DrawCommand::Line { start: Point::new(1, 1), end: Point::new(5, 1), color: Color::WHITE },
+    ]);
+    // With the clear and the present of the creation
+    assert_eq!(log.get_commands().len(), 4);
+
+    log.clear();
+    assert!(log.get_commands().is_empty());
+}
+
+#[test]
+fn recording_mirrored_transform_flips_images() {
+    let recording = RecordingRenderer::new(Box::new(SdlCanvas::new_headless(32, 32).unwrap()));
+    let log = recording.get_log();
+    let mut graphics = Graphics::with_renderer(Box::new(recording));
+    // 8x8
+    let sprite = graphics.new_image("tests/assets/sprite.png").unwrap();
+    log.clear();
+
+    graphics.translate(16., 0.);
+    graphics.scale(-1., 1.);
+    graphics.draw(&sprite, 0., 0., 0.).unwrap();
+    // Flipped by the image and by the transformation : not flipped
+    graphics.draw_full(&sprite, 0., 0., 0., -1., 1., 0., 0.).unwrap();
+
+    let copies: Vec<(Rect, bool, bool)> = log
+        .get_commands()
+        .into_iter()
+        .filter_map(|c| match c {
+            DrawCommand::Texture { dst, flip_horizontal, flip_vertical, .. } => Some((dst, flip_horizontal, flip_vertical)),
+            _ => Option::None,
+        })
+        .collect();
+    assert_eq!(copies, [
+        (Rect::new(8, 0, 8, 8), true, false),
+        (Rect::new(8, 0, 8, 8), false, false),
+    ]);
+}
+
+#[test]
+fn recording_transformed_polygon() {
+    let recording = RecordingRenderer::new(Box::new(SdlCanvas::new_headless(32, 32).unwrap()));
+    let log = recording.get_log();
+    let mut graphics = Graphics::with_renderer(Box::new(recording));
+    log.clear();
+
+    // Rotated a quarter turn (clockwise on the screen), then moved
+    graphics.translate(10., 5.);
+    graphics.rotate(90.);
+    let points = [Position2d { x: 0., y: 0. }, Position2d { x: 4., y: 0. }, Position2d { x: 0., y: 2. }];
+    graphics.polygon(DrawMode::Fill, &points, Some(Color::RED)).unwrap();
+    graphics.polygon(DrawMode::Line, &points, Some(Color::GREEN)).unwrap();
+
+    let expected = vec![Point::new(10, 5), Point::new(10, 9), Point::new(8, 5)];
+    assert_eq!(log.get_commands(), vec![
+        DrawCommand::Polygon { mode: DrawMode::Fill, points: expected.clone(), color: Color::RED },
+        DrawCommand::Polygon { mode: DrawMode::Line, points: expected, color: Color::GREEN },
+    ]);
+}
+
+#[test]
+fn recording_transformed_ellipse() {
+    let recording = RecordingRenderer::new(Box::new(SdlCanvas::new_headless(32, 32).unwrap()));
+    let log = recording.get_log();
+    let mut graphics = Graphics::with_renderer(Box::new(recording));
+    log.clear();
+
+    graphics.translate(16., 16.);
+    graphics.scale(2., 1.);
+    graphics.circle(DrawMode::Fill, 0., 0., 5., Some(Color::BLUE)).unwrap();
+
+    let commands = log.get_commands();
+    let points = match commands.as_slice() {
+        [DrawCommand::Polygon { mode: DrawMode::Fill, points, color: Color::BLUE }] => points.clone(),
+        other => panic!("Unexpected commands {:?}", other),
+    };
+    // Radius of 10 on the screen : ceil(sqrt(10) * 6) segments
+    assert_eq!(points.len(), 19);
+    assert_eq!(points[0], Point::new(26, 16));
+    for (i, point) in points.iter().enumerate() {
+        let angle = (360. * i as f32 / 19.).to_radians();
+        let (x, y) = (5. * angle.cos(), 5. * angle.sin());
+        assert_eq!(*point, Point::new((16. + 2. * x).round() as i32, (16. + y).round() as i32), "point {}", i);
+    }
+    // An ellipse of 10 x 5 around (16, 16)
+    assert!(points.iter().all(|p| (p.x() - 16).abs() <= 10 && (p.y() - 16).abs() <= 5));
+}